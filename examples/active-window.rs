@@ -5,8 +5,8 @@ fn main() {
         Ok(active_window) => {
             println!("active window: {:#?}", active_window);
         }
-        Err(()) => {
-            println!("error occurred while getting the active window");
+        Err(err) => {
+            println!("error occurred while getting the active window: {}", err);
         }
     }
 }
@@ -0,0 +1,22 @@
+use active_win_pos_rs::{capture_window, get_active_window, CaptureArea};
+
+fn main() {
+    let screenshot = get_active_window()
+        .and_then(|window| capture_window(&window.window_id, CaptureArea::Frame));
+
+    match screenshot {
+        Ok(screenshot) => {
+            let png = screenshot
+                .to_png()
+                .expect("failed to encode the screenshot");
+            std::fs::write("active-window.png", png).expect("failed to write active-window.png");
+            println!(
+                "saved {}x{} screenshot to active-window.png",
+                screenshot.width, screenshot.height
+            );
+        }
+        Err(err) => {
+            println!("error occurred while capturing the active window: {}", err);
+        }
+    }
+}
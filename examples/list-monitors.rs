@@ -0,0 +1,26 @@
+use active_win_pos_rs::list_monitors;
+
+fn main() {
+    match list_monitors() {
+        Ok(monitors) => {
+            for monitor in monitors {
+                let marker = if monitor.is_primary { "*" } else { " " };
+                println!(
+                    "{} {} {}x{}+{}+{} @ {:.2} Hz ({}x{} mm)",
+                    marker,
+                    monitor.name,
+                    monitor.position.width,
+                    monitor.position.height,
+                    monitor.position.x,
+                    monitor.position.y,
+                    monitor.refresh_rate,
+                    monitor.width_mm,
+                    monitor.height_mm
+                );
+            }
+        }
+        Err(err) => {
+            println!("error occurred while listing monitors: {}", err);
+        }
+    }
+}
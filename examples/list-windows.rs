@@ -0,0 +1,15 @@
+use active_win_pos_rs::list_windows;
+
+fn main() {
+    match list_windows() {
+        Ok(windows) => {
+            for window in windows {
+                let marker = if window.is_active { "*" } else { " " };
+                println!("{} {} ({})", marker, window.title, window.app_name);
+            }
+        }
+        Err(err) => {
+            println!("error occurred while listing windows: {}", err);
+        }
+    }
+}
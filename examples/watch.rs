@@ -0,0 +1,18 @@
+use active_win_pos_rs::watch_active_window;
+
+fn main() {
+    let watcher = match watch_active_window() {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            println!("error occurred while watching the active window: {}", err);
+            return;
+        }
+    };
+
+    for active_window in watcher {
+        match active_window {
+            Ok(active_window) => println!("active window: {:#?}", active_window),
+            Err(err) => println!("no active window: {}", err),
+        }
+    }
+}
@@ -1,15 +1,163 @@
 use std::path::PathBuf;
 
-use super::window_position::WindowPosition;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
+use super::backend::Backend;
+use super::desktop_entry::DesktopEntry;
+use super::error::Error;
+use super::monitor::Monitor;
+use super::packaging::Packaging;
+use super::process_info::ProcessInfo;
+use super::window_id::WindowId;
+use super::window_position::{CoordinateSpace, WindowPosition};
+use super::window_state::WindowState;
+use super::window_type::WindowType;
+use super::workspace::Workspace;
+
+/// With the `serde` feature enabled this serializes to a flat record with the field names below,
+/// e.g. in JSON:
+///
+/// ```json
+/// {
+///   "title": "README.md - Visual Studio Code",
+///   "process_path": "/usr/share/code/code",
+///   "app_name": "Code",
+///   "wm_instance": "code",
+///   "wm_class": "Code",
+///   "gtk_application_id": null,
+///   "window_role": "browser-window",
+///   "window_id": "81788931",
+///   "process_id": 4242,
+///   "position": { "x": 0.0, "y": 27.0, "width": 1920.0, "height": 1053.0 },
+///   "client_rect": { "x": 0.0, "y": 27.0, "width": 1920.0, "height": 1053.0 },
+///   "frame_rect": { "x": 0.0, "y": 0.0, "width": 1920.0, "height": 1080.0 },
+///   "is_active": true,
+///   "backend": "x11",
+///   "monitor": {
+///     "name": "DP-1",
+///     "position": { "x": 0.0, "y": 0.0, "width": 1920.0, "height": 1080.0 },
+///     "is_primary": true,
+///     "refresh_rate": 60.0,
+///     "width_mm": 527,
+///     "height_mm": 296,
+///     "scale_factor": 1.0,
+///     "coordinate_space": "physical"
+///   },
+///   "coordinate_space": "physical",
+///   "state": "MAXIMIZED_VERT | MAXIMIZED_HORZ",
+///   "window_type": "normal",
+///   "transient_for": null,
+///   "workspace": { "index": 0, "name": "Main", "is_current": true }
+/// }
+/// ```
+///
+/// `process_path` and `window_id` are always strings, see [`WindowId`] for the id format; paths that are not valid UTF-8 are converted lossily.
+/// Missing fields take their default value when deserializing, so records written by older
+/// versions keep loading as fields are added.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
 pub struct ActiveWindow {
     pub title: String,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_path_lossy"))]
     pub process_path: PathBuf,
     pub app_name: String,
-    pub window_id: String,
+    /// Instance name, the first part of `WM_CLASS` on X11 and the resource name on KDE Plasma.
+    /// Empty if the backend does not report it.
+    pub wm_instance: String,
+    /// Class name, the second part of `WM_CLASS` on X11 and the resource class on KDE Plasma.
+    pub wm_class: String,
+    /// Application id GTK sets in `_GTK_APPLICATION_ID` on X11.
+    pub gtk_application_id: Option<String>,
+    /// Role the application gave the window to tell its windows apart, from `WM_WINDOW_ROLE`.
+    pub window_role: Option<String>,
+    pub window_id: WindowId,
     pub process_id: u64,
+    /// Same as [`client_rect`](ActiveWindow::client_rect) on X11 and
+    /// [`frame_rect`](ActiveWindow::frame_rect) on the other backends.
     pub position: WindowPosition,
+    /// Area of the window's content, without the decorations drawn by the window manager.
+    pub client_rect: WindowPosition,
+    /// Outer bounds of the window including its title bar and borders, the same as
+    /// `client_rect` for undecorated windows.
+    pub frame_rect: WindowPosition,
+    /// Whether this window has focus. Always `true` for [`get_active_window`](crate::get_active_window),
+    /// set on the focused entry of [`list_windows`](crate::list_windows).
+    pub is_active: bool,
+    /// Backend that read this window, [`Backend::Auto`] if it was not read from a window system.
+    pub backend: Backend,
+    /// Monitor containing the largest part of the window, `None` if it is off screen or the
+    /// backend does not know about monitors.
+    pub monitor: Option<Monitor>,
+    /// Unit of [`position`](ActiveWindow::position), see [`physical_position`](ActiveWindow::physical_position)
+    /// and [`logical_position`](ActiveWindow::logical_position) to convert it.
+    pub coordinate_space: CoordinateSpace,
+    /// Minimized, maximized, fullscreen and similar states, empty if the backend does not report them.
+    pub state: WindowState,
+    /// Kind of window, e.g. to skip dialogs and popups. [`WindowType::Normal`] if the backend
+    /// does not report it.
+    pub window_type: WindowType,
+    /// Main window this one is a transient (dialog, popup) for, `WM_TRANSIENT_FOR` on X11.
+    pub transient_for: Option<WindowId>,
+    /// Workspace the window is on, `None` if it is shown on all of them or the backend does
+    /// not report workspaces.
+    pub workspace: Option<Workspace>,
+}
+
+impl ActiveWindow {
+    /// Scale factor of the window's [`monitor`](ActiveWindow::monitor), `1.0` if that is unknown.
+    pub fn scale_factor(&self) -> f64 {
+        self.monitor
+            .as_ref()
+            .map_or(1.0, |monitor| monitor.scale_factor)
+    }
+
+    /// Reads details about the window's process, such as its command line and working
+    /// directory, see [`ProcessInfo::for_pid`].
+    pub fn process_info(&self) -> Result<ProcessInfo, Error> {
+        ProcessInfo::for_pid(self.process_id)
+    }
+
+    /// Detects whether the application runs from a Flatpak, Snap or AppImage, whose
+    /// [`app_id`](Packaging::app_id) names it better than `process_path`, which points into
+    /// the sandbox or bundle. See [`Packaging::for_pid`].
+    pub fn packaging(&self) -> Result<Option<Packaging>, Error> {
+        Packaging::for_pid(self.process_id)
+    }
+
+    /// Looks up the application's `.desktop` file for its display name, icon and categories,
+    /// see [`DesktopEntry::for_window`].
+    pub fn desktop_entry(&self) -> Result<Option<DesktopEntry>, Error> {
+        DesktopEntry::for_window(self)
+    }
+
+    /// Position of the window in physical pixels.
+    pub fn physical_position(&self) -> WindowPosition {
+        match self.coordinate_space {
+            CoordinateSpace::Physical => self.position.clone(),
+            CoordinateSpace::Logical => self.position.to_physical(self.scale_factor()),
+        }
+    }
+
+    /// Position of the window in logical coordinates.
+    pub fn logical_position(&self) -> WindowPosition {
+        match self.coordinate_space {
+            CoordinateSpace::Physical => self.position.to_logical(self.scale_factor()),
+            CoordinateSpace::Logical => self.position.clone(),
+        }
+    }
+
+    /// Position of the window relative to the origin of its [`monitor`](ActiveWindow::monitor).
+    pub fn position_on_monitor(&self) -> Option<WindowPosition> {
+        self.monitor.as_ref().map(|monitor| {
+            WindowPosition::new(
+                self.position.x - monitor.position.x,
+                self.position.y - monitor.position.y,
+                self.position.width,
+                self.position.height,
+            )
+        })
+    }
 }
 
 impl PartialEq for ActiveWindow {
@@ -17,3 +165,11 @@ impl PartialEq for ActiveWindow {
         self.process_id == other.process_id && self.window_id == other.window_id
     }
 }
+
+#[cfg(feature = "serde")]
+fn serialize_path_lossy<S: serde::Serializer>(
+    path: &std::path::Path,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&path.to_string_lossy())
+}
@@ -0,0 +1,130 @@
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::error::Error;
+use super::registry::registered_backends;
+
+// Forces a backend for sessions that do not pick one explicitly
+const BACKEND_ENV: &str = "ACTIVE_WIN_BACKEND";
+
+/// Window system backend used to answer queries.
+///
+/// [`Backend::Auto`] picks one from the environment: on Linux KWin is tried first when
+/// `WAYLAND_DISPLAY` is set, falling back to X11 (XWayland) if it fails. Any other value
+/// forces that backend, and its errors are returned instead of falling back.
+///
+/// Unless a backend is chosen with [`ActiveWindowSessionBuilder::backend`](crate::ActiveWindowSessionBuilder::backend),
+/// the `ACTIVE_WIN_BACKEND` environment variable can force one, e.g. `ACTIVE_WIN_BACKEND=x11`.
+/// Names are the ones produced by [`Display`](fmt::Display): `auto`, `x11`, `kwin`, `windows`,
+/// `macos`, or the name of a registered custom backend. With the `serde` feature the backend is
+/// serialized as that name, so deserializing a custom backend requires it to be registered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Backend {
+    #[default]
+    Auto,
+    /// X11 through `xcb`, including XWayland on Wayland sessions.
+    X11,
+    /// KDE Plasma's KWin compositor, through KWin scripts over D-Bus.
+    KWin,
+    /// Win32 API.
+    Windows,
+    /// Core Graphics and AppKit.
+    MacOS,
+    /// A backend added with [`register_backend`](crate::register_backend), identified by its name.
+    Custom(&'static str),
+}
+
+impl Backend {
+    /// Whether this backend can be used on the platform the crate was built for.
+    pub fn is_available(self) -> bool {
+        match self {
+            Backend::Auto => true,
+            Backend::X11 | Backend::KWin => cfg!(target_os = "linux"),
+            Backend::Windows => cfg!(target_os = "windows"),
+            Backend::MacOS => cfg!(target_os = "macos"),
+            Backend::Custom(_) => registered_backends()
+                .iter()
+                .any(|api| api.backend() == self),
+        }
+    }
+
+    pub(crate) fn unavailable(self) -> Error {
+        Error::BackendUnavailable(
+            format!("the {} backend is not available on this platform", self).into(),
+        )
+    }
+
+    pub(crate) fn check_available(self) -> Result<Self, Error> {
+        if self.is_available() {
+            Ok(self)
+        } else {
+            Err(self.unavailable())
+        }
+    }
+
+    // Resolves `Auto` through `ACTIVE_WIN_BACKEND`, so it is still `Auto` if that is unset
+    pub(crate) fn resolve(self) -> Result<Self, Error> {
+        if self != Backend::Auto {
+            return Ok(self);
+        }
+
+        match env::var(BACKEND_ENV) {
+            Ok(name) if !name.trim().is_empty() => name.parse::<Backend>()?.check_available(),
+            _ => Ok(Backend::Auto),
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Backend::Auto => "auto",
+            Backend::X11 => "x11",
+            Backend::KWin => "kwin",
+            Backend::Windows => "windows",
+            Backend::MacOS => "macos",
+            Backend::Custom(name) => name,
+        })
+    }
+}
+
+impl FromStr for Backend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(Backend::Auto),
+            "x11" => Ok(Backend::X11),
+            "kwin" => Ok(Backend::KWin),
+            "windows" => Ok(Backend::Windows),
+            "macos" => Ok(Backend::MacOS),
+            name => registered_backends()
+                .iter()
+                .map(|api| api.backend())
+                .find(|backend| backend.to_string().eq_ignore_ascii_case(name))
+                .ok_or_else(|| Error::InvalidBackend(s.to_owned())),
+        }
+    }
+}
+
+// Not derived, `Custom` holds a `&'static str` that could only be deserialized by borrowing
+#[cfg(feature = "serde")]
+impl Serialize for Backend {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Backend {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
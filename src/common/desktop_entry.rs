@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::active_window::ActiveWindow;
+use super::error::Error;
+
+/// Application metadata from the freedesktop `.desktop` file of a window's application, see
+/// [`ActiveWindow::desktop_entry`](crate::ActiveWindow::desktop_entry).
+///
+/// Names are localized for the locale set in `LC_ALL`, `LC_MESSAGES` or `LANG`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct DesktopEntry {
+    /// Desktop file id, e.g. `org.mozilla.firefox.desktop`.
+    pub id: String,
+    pub path: PathBuf,
+    /// Display name of the application, e.g. `Firefox`.
+    pub name: String,
+    /// Generic description, e.g. `Web Browser`.
+    pub generic_name: Option<String>,
+    /// Icon name to look up in the icon theme, or an absolute path to the icon.
+    pub icon: Option<String>,
+    /// Menu categories, e.g. `Network` and `WebBrowser`.
+    pub categories: Vec<String>,
+}
+
+impl DesktopEntry {
+    /// Finds the desktop file of the window's application in the `applications` directories
+    /// under `XDG_DATA_HOME` and `XDG_DATA_DIRS`. Returns `None` if no entry matches.
+    ///
+    /// Entries are matched by Flatpak or Snap id, GTK application id, `StartupWMClass` and
+    /// finally by the executable. Every call scans the directories again, so keep the result
+    /// around when resolving many windows of the same application.
+    ///
+    /// Currently supported on Linux.
+    pub fn for_window(window: &ActiveWindow) -> Result<Option<Self>, Error> {
+        #[cfg(target_os = "linux")]
+        return Ok(crate::linux::find_desktop_entry(window));
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = window;
+            Err(Error::Unsupported)
+        }
+    }
+}
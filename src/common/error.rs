@@ -0,0 +1,75 @@
+use std::error::Error as StdError;
+use std::fmt;
+
+use super::window_id::WindowId;
+
+pub type BoxedError = Box<dyn StdError + Send + Sync + 'static>;
+
+/// Errors that can occur while querying the active window.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// No display server to talk to, e.g. neither `DISPLAY` nor `WAYLAND_DISPLAY` is set.
+    NoDisplayServer,
+    /// Connecting to the display server failed.
+    ConnectionFailed(BoxedError),
+    /// The window manager does not implement the EWMH hints needed to find the active window.
+    EwmhUnsupported,
+    /// No window currently has focus.
+    NoActiveWindow,
+    /// A window property required to describe the window could not be read.
+    PropertyMissing(&'static str),
+    /// The operating system denied access to the window or its process.
+    PermissionDenied,
+    /// The window system backend could not be used in this session.
+    BackendUnavailable(BoxedError),
+    /// A call into the platform window system API failed.
+    Platform(BoxedError),
+    /// The operation is not implemented for this platform or backend.
+    Unsupported,
+    /// A string could not be parsed as a [`WindowId`](crate::WindowId), or the id was handed
+    /// to a backend it does not belong to.
+    InvalidWindowId(String),
+    /// The window an action was asked for does not exist (anymore).
+    WindowNotFound(WindowId),
+    /// A string, e.g. the value of `ACTIVE_WIN_BACKEND`, does not name a [`Backend`](crate::Backend).
+    InvalidBackend(String),
+    /// An argument cannot be passed on to the window system, e.g. a window geometry that is
+    /// not finite or has a negative size.
+    InvalidInput(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoDisplayServer => f.write_str("no display server found"),
+            Error::ConnectionFailed(err) => {
+                write!(f, "failed to connect to the display server: {}", err)
+            }
+            Error::EwmhUnsupported => f.write_str("window manager does not support EWMH"),
+            Error::NoActiveWindow => f.write_str("no active window"),
+            Error::PropertyMissing(property) => {
+                write!(f, "window property {} is missing", property)
+            }
+            Error::PermissionDenied => f.write_str("permission denied"),
+            Error::BackendUnavailable(err) => write!(f, "backend unavailable: {}", err),
+            Error::Platform(err) => write!(f, "platform API error: {}", err),
+            Error::Unsupported => f.write_str("operation not supported by this backend"),
+            Error::InvalidWindowId(id) => write!(f, "invalid window id: {}", id),
+            Error::InvalidBackend(name) => write!(f, "unknown backend: {}", name),
+            Error::WindowNotFound(id) => write!(f, "window {} not found", id),
+            Error::InvalidInput(input) => write!(f, "invalid input: {}", input),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::ConnectionFailed(err)
+            | Error::BackendUnavailable(err)
+            | Error::Platform(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use super::active_window::ActiveWindow;
+use super::backend::Backend;
+use super::error::Error;
+use super::monitor::{find_monitor, Monitor};
+use super::platform_api::PlatformApi;
+use super::registry::install_exclusive_backend;
+use super::screenshot::{CaptureArea, Screenshot};
+use super::watcher::{StopHandle, WakeFn, WindowEvents};
+use super::window_icon::WindowIcon;
+use super::window_id::WindowId;
+use super::window_position::WindowPosition;
+use super::window_state::WindowState;
+use super::workspace::Workspace;
+
+const MOCK_BACKEND: Backend = Backend::Custom("mock");
+
+type ErrorFn = Arc<dyn Fn() -> Error + Send + Sync>;
+
+#[derive(Clone)]
+#[allow(clippy::large_enum_variant)]
+enum MockState {
+    Window(ActiveWindow),
+    Error(ErrorFn),
+}
+
+impl MockState {
+    fn to_result(&self) -> Result<ActiveWindow, Error> {
+        match self {
+            MockState::Window(window) => Ok(window.clone()),
+            MockState::Error(error) => Err(error()),
+        }
+    }
+}
+
+struct Script {
+    start: Instant,
+    initial: MockState,
+    // Sorted by the time since `start` at which each change happens
+    changes: Vec<(Duration, MockState)>,
+    windows: Vec<ActiveWindow>,
+    monitors: Vec<Monitor>,
+    workspaces: Vec<Workspace>,
+    idle_time: Duration,
+    icons: HashMap<WindowId, Vec<WindowIcon>>,
+}
+
+impl Script {
+    fn current(&self) -> &MockState {
+        let elapsed = self.start.elapsed();
+
+        self.changes
+            .iter()
+            .take_while(|(at, _)| *at <= elapsed)
+            .last()
+            .map_or(&self.initial, |(_, state)| state)
+    }
+}
+
+struct Shared {
+    script: Mutex<Script>,
+    // Notified when the script changes or a watcher is stopped
+    changed: Condvar,
+}
+
+/// Scripted backend for testing code that depends on the active window, available with the
+/// `mock` feature.
+///
+/// Once [`install`](MockBackend::install)ed it answers every query, including the free
+/// functions, [`ActiveWindowSession`](crate::ActiveWindowSession)s and watchers, from its
+/// script instead of the display server. The script starts with an active window (or error)
+/// and can hold focus changes that happen at a given time after the mock was created:
+///
+/// ```
+/// use std::time::Duration;
+/// use active_win_pos_rs::{ActiveWindow, Error, MockBackend};
+///
+/// let mock = MockBackend::new();
+/// mock.set_active_window(ActiveWindow {
+///     title: "Editor".to_owned(),
+///     ..Default::default()
+/// });
+/// mock.push_change(
+///     Duration::from_millis(100),
+///     ActiveWindow {
+///         title: "Browser".to_owned(),
+///         ..Default::default()
+///     },
+/// );
+/// mock.push_error(Duration::from_millis(200), || Error::NoActiveWindow);
+/// mock.install();
+///
+/// let titles = active_win_pos_rs::watch_active_window()
+///     .unwrap()
+///     .map(|window| window.map(|window| window.title))
+///     .collect::<Vec<_>>();
+/// assert!(matches!(
+///     &titles[..],
+///     [Ok(editor), Ok(browser), Err(Error::NoActiveWindow)] if editor == "Editor" && browser == "Browser"
+/// ));
+/// ```
+///
+/// The mock is a cloneable handle, so the script can still be changed after installing it.
+/// Installed backends are global to the process, keep that in mind with tests running in parallel
+/// and [`uninstall`](MockBackend::uninstall) the mock when a test is done with it.
+#[derive(Clone)]
+pub struct MockBackend {
+    shared: Arc<Shared>,
+}
+
+impl MockBackend {
+    /// Creates a mock without an active window, queries fail with [`Error::NoActiveWindow`].
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                script: Mutex::new(Script {
+                    start: Instant::now(),
+                    initial: MockState::Error(Arc::new(|| Error::NoActiveWindow)),
+                    changes: Vec::new(),
+                    windows: Vec::new(),
+                    monitors: Vec::new(),
+                    workspaces: Vec::new(),
+                    idle_time: Duration::ZERO,
+                    icons: HashMap::new(),
+                }),
+                changed: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Makes the mock answer every query, replacing any mock installed before.
+    ///
+    /// Other backends are no longer tried, also not when a query fails or a backend is forced,
+    /// so errors come from the script. Capturing and controlling windows fail with
+    /// [`Error::Unsupported`].
+    pub fn install(&self) {
+        install_exclusive_backend(Some(Arc::new(self.clone())));
+    }
+
+    /// Removes the installed mock, if any, so queries reach the real backends again.
+    pub fn uninstall() {
+        install_exclusive_backend(None);
+    }
+
+    /// Sets the window that is active before any scripted change.
+    pub fn set_active_window(&self, window: ActiveWindow) {
+        self.update(|script| script.initial = MockState::Window(window));
+    }
+
+    /// Makes queries fail with the error returned by `error` before any scripted change.
+    pub fn set_error(&self, error: impl Fn() -> Error + Send + Sync + 'static) {
+        self.update(|script| script.initial = MockState::Error(Arc::new(error)));
+    }
+
+    /// Makes `window` the active window `at` after the mock was created.
+    pub fn push_change(&self, at: Duration, window: ActiveWindow) {
+        self.push(at, MockState::Window(window));
+    }
+
+    /// Makes queries fail with the error returned by `error` from `at` after the mock was created.
+    pub fn push_error(&self, at: Duration, error: impl Fn() -> Error + Send + Sync + 'static) {
+        self.push(at, MockState::Error(Arc::new(error)));
+    }
+
+    /// Sets the windows returned by [`list_windows`](crate::list_windows). The one with the
+    /// same id as the current active window is marked with [`ActiveWindow::is_active`].
+    pub fn set_windows(&self, windows: Vec<ActiveWindow>) {
+        self.update(|script| script.windows = windows);
+    }
+
+    /// Sets the monitors returned by [`list_monitors`](crate::list_monitors).
+    pub fn set_monitors(&self, monitors: Vec<Monitor>) {
+        self.update(|script| script.monitors = monitors);
+    }
+
+    /// Sets the workspaces returned by [`workspaces`](crate::workspaces).
+    pub fn set_workspaces(&self, workspaces: Vec<Workspace>) {
+        self.update(|script| script.workspaces = workspaces);
+    }
+
+    /// Sets the time returned by [`idle_time`](crate::idle_time), zero unless set.
+    pub fn set_idle_time(&self, idle_time: Duration) {
+        self.update(|script| script.idle_time = idle_time);
+    }
+
+    /// Sets the icons returned by [`get_window_icons`](crate::get_window_icons) for `window_id`,
+    /// other windows have none.
+    pub fn set_window_icons(&self, window_id: WindowId, icons: Vec<WindowIcon>) {
+        self.update(|script| {
+            script.icons.insert(window_id, icons);
+        });
+    }
+
+    fn push(&self, at: Duration, state: MockState) {
+        self.update(|script| {
+            let index = script
+                .changes
+                .partition_point(|(change_at, _)| *change_at <= at);
+            script.changes.insert(index, (at, state));
+        });
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Script)) {
+        let mut script = self.shared.lock();
+        f(&mut script);
+        self.shared.changed.notify_all();
+    }
+}
+
+impl Default for MockBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Script> {
+        self.script.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Script {
+    // Fills in what the script left out: the backend and the monitor the window is on
+    fn window(&self, window: ActiveWindow) -> ActiveWindow {
+        let backend = match window.backend {
+            Backend::Auto => MOCK_BACKEND,
+            backend => backend,
+        };
+        let monitor = window
+            .monitor
+            .clone()
+            .or_else(|| find_monitor(&self.monitors, &window.position));
+
+        ActiveWindow {
+            backend,
+            monitor,
+            ..window
+        }
+    }
+}
+
+impl PlatformApi for MockBackend {
+    fn backend(&self) -> Backend {
+        MOCK_BACKEND
+    }
+
+    fn get_active_window(&self) -> Result<ActiveWindow, Error> {
+        let script = self.shared.lock();
+        script
+            .current()
+            .to_result()
+            .map(|window| script.window(window))
+    }
+
+    fn list_windows(&self) -> Result<Vec<ActiveWindow>, Error> {
+        let script = self.shared.lock();
+        let active_id = match script.current() {
+            MockState::Window(window) => Some(window.window_id.clone()),
+            MockState::Error(_) => None,
+        };
+
+        Ok(script
+            .windows
+            .iter()
+            .map(|window| {
+                script.window(ActiveWindow {
+                    is_active: Some(&window.window_id) == active_id.as_ref(),
+                    ..window.clone()
+                })
+            })
+            .collect())
+    }
+
+    fn list_monitors(&self) -> Result<Vec<Monitor>, Error> {
+        Ok(self.shared.lock().monitors.clone())
+    }
+
+    fn workspaces(&self) -> Result<Vec<Workspace>, Error> {
+        Ok(self.shared.lock().workspaces.clone())
+    }
+
+    fn idle_time(&self) -> Result<Duration, Error> {
+        Ok(self.shared.lock().idle_time)
+    }
+
+    fn get_window_icons(&self, window_id: &WindowId) -> Result<Vec<WindowIcon>, Error> {
+        Ok(self
+            .shared
+            .lock()
+            .icons
+            .get(window_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    // Not scripted, the mock has no pixels to capture and no windows to act on
+
+    fn capture_window(
+        &self,
+        _window_id: &WindowId,
+        _area: CaptureArea,
+    ) -> Result<Screenshot, Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn activate_window(&self, _window_id: &WindowId) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn move_resize_window(
+        &self,
+        _window_id: &WindowId,
+        _position: &WindowPosition,
+    ) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn set_window_state(
+        &self,
+        _window_id: &WindowId,
+        _state: WindowState,
+        _enable: bool,
+    ) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn close_window(&self, _window_id: &WindowId) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn watch(&self) -> Result<Box<dyn WindowEvents>, Error> {
+        let since = self.shared.lock().start.elapsed();
+
+        Ok(Box::new(MockWindowEvents {
+            shared: self.shared.clone(),
+            since,
+            reported_initial: false,
+            timer_for: None,
+        }))
+    }
+}
+
+// Reports the state when watching started, then every scripted change after that, and ends
+// once the script has been played
+struct MockWindowEvents {
+    shared: Arc<Shared>,
+    // Time since the script's start up to which changes have been reported
+    since: Duration,
+    reported_initial: bool,
+    // Deadline a timer thread is waiting for
+    timer_for: Option<Instant>,
+}
+
+#[allow(clippy::large_enum_variant)]
+enum MockStep {
+    Report(Result<ActiveWindow, Error>),
+    // Nothing to report until the next change is due
+    WaitUntil(Instant),
+    Done,
+}
+
+impl MockWindowEvents {
+    fn step(&mut self, script: &Script) -> MockStep {
+        if !self.reported_initial {
+            self.reported_initial = true;
+            let result = script.current().to_result();
+            return MockStep::Report(result.map(|window| script.window(window)));
+        }
+
+        let Some((at, state)) = script.changes.iter().find(|(at, _)| *at > self.since) else {
+            return MockStep::Done;
+        };
+
+        if *at > script.start.elapsed() {
+            return MockStep::WaitUntil(script.start + *at);
+        }
+
+        self.since = *at;
+        MockStep::Report(state.to_result().map(|window| script.window(window)))
+    }
+}
+
+impl WindowEvents for MockWindowEvents {
+    fn next_change(&mut self, stop: &StopHandle) -> Option<Result<ActiveWindow, Error>> {
+        let shared = self.shared.clone();
+        let mut script = shared.lock();
+
+        loop {
+            if stop.is_stopped() {
+                return None;
+            }
+
+            match self.step(&script) {
+                MockStep::Report(result) => return Some(result),
+                // Woken early if the script changes or the watcher is stopped
+                MockStep::WaitUntil(deadline) => {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    script = shared
+                        .changed
+                        .wait_timeout(script, timeout)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0;
+                }
+                MockStep::Done => return None,
+            }
+        }
+    }
+
+    fn waker(&self) -> WakeFn {
+        let shared = self.shared.clone();
+
+        Box::new(move || {
+            let _script = shared.lock();
+            shared.changed.notify_all();
+        })
+    }
+
+    fn poll_change(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<ActiveWindow, Error>>> {
+        let shared = self.shared.clone();
+        let step = self.step(&shared.lock());
+
+        match step {
+            MockStep::Report(result) => Poll::Ready(Some(result)),
+            MockStep::WaitUntil(deadline) => {
+                if self.timer_for != Some(deadline) {
+                    self.timer_for = Some(deadline);
+
+                    let waker = cx.waker().clone();
+                    std::thread::spawn(move || {
+                        std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                        waker.wake();
+                    });
+                }
+
+                Poll::Pending
+            }
+            MockStep::Done => Poll::Ready(None),
+        }
+    }
+}
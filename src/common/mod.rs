@@ -1,3 +1,22 @@
 pub mod active_window;
+pub mod backend;
+pub mod desktop_entry;
+pub mod error;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod monitor;
+pub mod packaging;
 pub mod platform_api;
+#[cfg(feature = "png")]
+mod png;
+pub mod process_info;
+pub mod registry;
+pub mod screenshot;
+pub mod session;
+pub mod watcher;
+pub mod window_icon;
+pub mod window_id;
 pub mod window_position;
+pub mod window_state;
+pub mod window_type;
+pub mod workspace;
@@ -0,0 +1,80 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::window_position::{CoordinateSpace, WindowPosition};
+
+/// A screen attached to the display, see [`list_monitors`](crate::list_monitors).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct Monitor {
+    /// Output name, e.g. `DP-1` or `eDP-1`.
+    pub name: String,
+    /// Area the monitor covers in the same coordinates as window positions.
+    pub position: WindowPosition,
+    pub is_primary: bool,
+    /// Refresh rate in Hz, `0.0` if unknown.
+    pub refresh_rate: f64,
+    /// Physical width in millimeters, `0` if unknown.
+    pub width_mm: u32,
+    /// Physical height in millimeters, `0` if unknown.
+    pub height_mm: u32,
+    /// Ratio of physical pixels to logical coordinates, `1.0` if unknown.
+    ///
+    /// On X11 this comes from the `Xft.dpi` resource (`dpi / 96`), which applies to every
+    /// monitor. KWin reports the scale configured for each output.
+    pub scale_factor: f64,
+    /// Unit of [`position`](Monitor::position).
+    pub coordinate_space: CoordinateSpace,
+}
+
+impl Monitor {
+    /// Area the monitor covers in physical pixels.
+    pub fn physical_position(&self) -> WindowPosition {
+        match self.coordinate_space {
+            CoordinateSpace::Physical => self.position.clone(),
+            CoordinateSpace::Logical => self.position.to_physical(self.scale_factor),
+        }
+    }
+
+    /// Area the monitor covers in logical coordinates.
+    pub fn logical_position(&self) -> WindowPosition {
+        match self.coordinate_space {
+            CoordinateSpace::Physical => self.position.to_logical(self.scale_factor),
+            CoordinateSpace::Logical => self.position.clone(),
+        }
+    }
+
+    /// Physical pixel density computed from the monitor's size, `None` if that is unknown.
+    pub fn dpi(&self) -> Option<f64> {
+        if self.width_mm == 0 {
+            return None;
+        }
+
+        Some(self.physical_position().width * 25.4 / f64::from(self.width_mm))
+    }
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            position: WindowPosition::default(),
+            is_primary: false,
+            refresh_rate: 0.0,
+            width_mm: 0,
+            height_mm: 0,
+            scale_factor: 1.0,
+            coordinate_space: CoordinateSpace::default(),
+        }
+    }
+}
+
+// Monitor covering the largest part of `position`, `None` if it is on none of them
+pub(crate) fn find_monitor(monitors: &[Monitor], position: &WindowPosition) -> Option<Monitor> {
+    monitors
+        .iter()
+        .map(|monitor| (monitor.position.intersection_area(position), monitor))
+        .filter(|(area, _)| *area > 0.0)
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, monitor)| monitor.clone())
+}
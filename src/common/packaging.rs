@@ -0,0 +1,47 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::error::Error;
+
+/// Format an application was packaged and shipped in, see [`Packaging`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum PackageFormat {
+    Flatpak,
+    Snap,
+    AppImage,
+}
+
+/// How a sandboxed or bundled application was packaged, see
+/// [`ActiveWindow::packaging`](crate::ActiveWindow::packaging).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Packaging {
+    pub format: PackageFormat,
+    /// Id the application is known by in its package format: the Flatpak application id
+    /// (e.g. `org.mozilla.firefox`), the snap name, or the AppImage file name without
+    /// its extension.
+    pub app_id: String,
+}
+
+impl Packaging {
+    /// Detects how the process with the given id was packaged, `None` for an application
+    /// that is not sandboxed or bundled.
+    ///
+    /// Currently supported on Linux, from the traces each format leaves in `/proc/<pid>`.
+    pub fn for_pid(pid: u64) -> Result<Option<Self>, Error> {
+        #[cfg(target_os = "linux")]
+        return Ok(crate::linux::get_process_packaging(pid));
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = pid;
+            Err(Error::Unsupported)
+        }
+    }
+}
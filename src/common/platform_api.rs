@@ -1,7 +1,104 @@
+use std::time::Duration;
+
 use super::active_window::ActiveWindow;
+use super::backend::Backend;
+use super::error::Error;
+use super::monitor::Monitor;
+use super::screenshot::{CaptureArea, Screenshot};
+use super::watcher::WindowEvents;
+use super::window_icon::WindowIcon;
+use super::window_id::WindowId;
 use super::window_position::WindowPosition;
+use super::window_state::WindowState;
+use super::workspace::Workspace;
+
+/// A source of window information, such as the built-in X11 and KWin backends.
+///
+/// Implement it to plug in another window system and add it with
+/// [`register_backend`](crate::register_backend). Only
+/// [`get_active_window`](PlatformApi::get_active_window) is required, the other queries
+/// fail with [`Error::Unsupported`] unless overridden.
+pub trait PlatformApi: Send + Sync {
+    /// Identifies the backend, [`Backend::Custom`] for backends outside this crate.
+    fn backend(&self) -> Backend;
+
+    /// Whether the backend applies to the current session. [`Backend::Auto`] only tries
+    /// available backends, one that is forced is used regardless.
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn get_position(&self) -> Result<WindowPosition, Error> {
+        Ok(self.get_active_window()?.position)
+    }
+
+    fn get_active_window(&self) -> Result<ActiveWindow, Error>;
+
+    fn list_windows(&self) -> Result<Vec<ActiveWindow>, Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn list_monitors(&self) -> Result<Vec<Monitor>, Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn workspaces(&self) -> Result<Vec<Workspace>, Error> {
+        Err(Error::Unsupported)
+    }
+
+    fn watch(&self) -> Result<Box<dyn WindowEvents>, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Time since the user last used the keyboard or mouse.
+    fn idle_time(&self) -> Result<Duration, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Every size of the window's icon the application provides.
+    fn get_window_icons(&self, _window_id: &WindowId) -> Result<Vec<WindowIcon>, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Takes a picture of the window as it is shown on screen.
+    fn capture_window(
+        &self,
+        _window_id: &WindowId,
+        _area: CaptureArea,
+    ) -> Result<Screenshot, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Focuses the window and raises it, restoring it if it is minimized.
+    ///
+    /// Ids from another backend fail with [`Error::InvalidWindowId`], so [`Backend::Auto`]
+    /// moves on to the backend the id belongs to.
+    fn activate_window(&self, _window_id: &WindowId) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Moves and resizes the window, `position` is in the backend's coordinate space.
+    fn move_resize_window(
+        &self,
+        _window_id: &WindowId,
+        _position: &WindowPosition,
+    ) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Sets (`enable`) or clears every flag in `state`.
+    fn set_window_state(
+        &self,
+        _window_id: &WindowId,
+        _state: WindowState,
+        _enable: bool,
+    ) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
 
-pub trait PlatformApi {
-    fn get_position(&self) -> Result<WindowPosition, ()>;
-    fn get_active_window(&self) -> Result<ActiveWindow, ()>;
+    /// Asks the window to close, the application may still keep it open, e.g. to ask for
+    /// unsaved changes.
+    fn close_window(&self, _window_id: &WindowId) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
 }
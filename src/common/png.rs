@@ -0,0 +1,13 @@
+use std::io;
+
+// 8-bit RGBA rows from top to bottom, see `WindowIcon::to_png` and `Screenshot::to_png`
+pub fn encode_rgba(width: u32, height: u32, rgba: &[u8]) -> io::Result<Vec<u8>> {
+    let mut png = Vec::new();
+
+    let mut encoder = png::Encoder::new(&mut png, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(rgba)?;
+
+    Ok(png)
+}
@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::error::Error;
+
+/// Details about the process owning a window, see [`ActiveWindow::process_info`](crate::ActiveWindow::process_info).
+///
+/// Fields the process does not let us read, e.g. the working directory of another user's
+/// process, are left empty.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct ProcessInfo {
+    pub pid: u64,
+    /// Command line arguments, starting with the program as it was started.
+    pub cmdline: Vec<String>,
+    /// Current working directory.
+    pub cwd: Option<PathBuf>,
+    pub parent_pid: Option<u64>,
+    /// Real user id of the owner.
+    pub uid: Option<u32>,
+    pub username: Option<String>,
+    pub start_time: Option<SystemTime>,
+    /// Whether the executable was deleted or replaced since the process started, e.g. by an
+    /// update, so [`ActiveWindow::process_path`](crate::ActiveWindow::process_path) no longer
+    /// refers to the running program.
+    pub exe_deleted: bool,
+}
+
+impl ProcessInfo {
+    /// Reads the details of the process with the given id.
+    ///
+    /// Currently supported on Linux, from `/proc/<pid>`.
+    pub fn for_pid(pid: u64) -> Result<Self, Error> {
+        #[cfg(target_os = "linux")]
+        return crate::linux::read_process_info(pid);
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = pid;
+            Err(Error::Unsupported)
+        }
+    }
+}
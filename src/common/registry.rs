@@ -0,0 +1,170 @@
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+
+use super::active_window::ActiveWindow;
+use super::backend::Backend;
+use super::error::Error;
+use super::monitor::Monitor;
+use super::platform_api::PlatformApi;
+use super::screenshot::{CaptureArea, Screenshot};
+use super::watcher::WindowEvents;
+use super::window_icon::WindowIcon;
+use super::window_id::WindowId;
+use super::window_position::WindowPosition;
+use super::window_state::WindowState;
+use super::workspace::Workspace;
+
+static REGISTERED_BACKENDS: RwLock<Vec<Arc<dyn PlatformApi>>> = RwLock::new(Vec::new());
+
+// An installed `MockBackend`, answers every query in place of all other backends
+#[cfg(feature = "mock")]
+static EXCLUSIVE_BACKEND: RwLock<Option<Arc<dyn PlatformApi>>> = RwLock::new(None);
+
+/// Adds a backend that takes part in every query, including the free functions such as
+/// [`get_active_window`](crate::get_active_window).
+///
+/// With [`Backend::Auto`] registered backends are tried before the built-in ones, in the order
+/// they were registered, and the next one is tried if a backend fails. A registered backend can
+/// also be forced by its [`Backend`] value, or by its name in `ACTIVE_WIN_BACKEND`.
+/// Registering a backend with the same [`Backend`] value again replaces the earlier one.
+pub fn register_backend(api: impl PlatformApi + 'static) {
+    let mut backends = REGISTERED_BACKENDS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    let backend = api.backend();
+    backends.retain(|registered| registered.backend() != backend);
+    backends.push(Arc::new(api));
+}
+
+pub(crate) fn registered_backends() -> Vec<Arc<dyn PlatformApi>> {
+    REGISTERED_BACKENDS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+#[cfg(feature = "mock")]
+pub(crate) fn install_exclusive_backend(api: Option<Arc<dyn PlatformApi>>) {
+    *EXCLUSIVE_BACKEND
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = api;
+}
+
+// Answers queries from the selected backend, or from the first available one that succeeds
+pub(crate) struct BackendChain {
+    backend: Backend,
+    builtin: Vec<Arc<dyn PlatformApi>>,
+}
+
+impl BackendChain {
+    pub fn new(backend: Backend) -> Self {
+        Self {
+            backend,
+            builtin: crate::builtin_backends(),
+        }
+    }
+
+    fn query<T>(&self, f: impl Fn(&dyn PlatformApi) -> Result<T, Error>) -> Result<T, Error> {
+        // Its errors are the script's, falling through would reach the display server
+        #[cfg(feature = "mock")]
+        if let Some(api) = EXCLUSIVE_BACKEND
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+        {
+            return f(api.as_ref());
+        }
+
+        // Read on every query, so backends registered after the chain was created are used too
+        let mut backends = registered_backends();
+        backends.extend(self.builtin.iter().cloned());
+
+        match self.backend.resolve()? {
+            Backend::Auto => {
+                let mut last_err = None;
+                for api in backends.iter().filter(|api| api.is_available()) {
+                    match f(api.as_ref()) {
+                        Ok(result) => return Ok(result),
+                        Err(err) => last_err = Some(err),
+                    }
+                }
+
+                Err(last_err.unwrap_or(Error::NoDisplayServer))
+            }
+            backend => {
+                let api = backends
+                    .iter()
+                    .find(|api| api.backend() == backend)
+                    .ok_or_else(|| backend.unavailable())?;
+
+                f(api.as_ref())
+            }
+        }
+    }
+
+    pub fn get_position(&self) -> Result<WindowPosition, Error> {
+        self.query(|api| api.get_position())
+    }
+
+    pub fn get_active_window(&self) -> Result<ActiveWindow, Error> {
+        self.query(|api| api.get_active_window())
+    }
+
+    pub fn list_windows(&self) -> Result<Vec<ActiveWindow>, Error> {
+        self.query(|api| api.list_windows())
+    }
+
+    pub fn list_monitors(&self) -> Result<Vec<Monitor>, Error> {
+        self.query(|api| api.list_monitors())
+    }
+
+    pub fn workspaces(&self) -> Result<Vec<Workspace>, Error> {
+        self.query(|api| api.workspaces())
+    }
+
+    pub fn watch(&self) -> Result<Box<dyn WindowEvents>, Error> {
+        self.query(|api| api.watch())
+    }
+
+    pub fn idle_time(&self) -> Result<Duration, Error> {
+        self.query(|api| api.idle_time())
+    }
+
+    pub fn get_window_icons(&self, window_id: &WindowId) -> Result<Vec<WindowIcon>, Error> {
+        self.query(|api| api.get_window_icons(window_id))
+    }
+
+    pub fn capture_window(
+        &self,
+        window_id: &WindowId,
+        area: CaptureArea,
+    ) -> Result<Screenshot, Error> {
+        self.query(|api| api.capture_window(window_id, area))
+    }
+
+    pub fn activate_window(&self, window_id: &WindowId) -> Result<(), Error> {
+        self.query(|api| api.activate_window(window_id))
+    }
+
+    pub fn move_resize_window(
+        &self,
+        window_id: &WindowId,
+        position: &WindowPosition,
+    ) -> Result<(), Error> {
+        self.query(|api| api.move_resize_window(window_id, position))
+    }
+
+    pub fn set_window_state(
+        &self,
+        window_id: &WindowId,
+        state: WindowState,
+        enable: bool,
+    ) -> Result<(), Error> {
+        self.query(|api| api.set_window_state(window_id, state, enable))
+    }
+
+    pub fn close_window(&self, window_id: &WindowId) -> Result<(), Error> {
+        self.query(|api| api.close_window(window_id))
+    }
+}
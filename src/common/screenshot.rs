@@ -0,0 +1,31 @@
+use super::window_position::WindowPosition;
+
+/// Part of a window to capture, see [`capture_window`](crate::capture_window).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CaptureArea {
+    /// The window with its title bar and borders, see [`ActiveWindow::frame_rect`](crate::ActiveWindow::frame_rect).
+    #[default]
+    Frame,
+    /// Only the window's content, see [`ActiveWindow::client_rect`](crate::ActiveWindow::client_rect).
+    Client,
+}
+
+/// Image of a window as shown on screen, see [`capture_window`](crate::capture_window).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Screenshot {
+    /// Screen area the image was taken from. Parts of the window outside the screen are
+    /// cut off, so this may be smaller than the window or even empty.
+    pub position: WindowPosition,
+    pub width: u32,
+    pub height: u32,
+    /// Rows from top to bottom, four bytes per pixel, alpha is always opaque.
+    pub rgba: Vec<u8>,
+}
+
+impl Screenshot {
+    /// Encodes the image as PNG, available with the `png` feature.
+    #[cfg(feature = "png")]
+    pub fn to_png(&self) -> std::io::Result<Vec<u8>> {
+        super::png::encode_rgba(self.width, self.height, &self.rgba)
+    }
+}
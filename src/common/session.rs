@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use super::active_window::ActiveWindow;
+use super::backend::Backend;
+use super::error::Error;
+use super::monitor::Monitor;
+use super::registry::BackendChain;
+use super::screenshot::{CaptureArea, Screenshot};
+#[cfg(feature = "async")]
+use super::watcher::ActiveWindowStream;
+use super::watcher::ActiveWindowWatcher;
+use super::window_icon::WindowIcon;
+use super::window_id::WindowId;
+use super::window_position::WindowPosition;
+use super::window_state::WindowState;
+use super::workspace::Workspace;
+
+/// Long-lived handle for querying the active window repeatedly.
+///
+/// Unlike [`get_active_window`](crate::get_active_window), which sets everything up
+/// on each call, a session keeps its display server connection (and the X11 atoms
+/// it needs) open between queries. It is `Send` and `Sync`, so it can be moved
+/// into or shared with a worker thread.
+pub struct ActiveWindowSession {
+    api: BackendChain,
+}
+
+impl ActiveWindowSession {
+    /// Creates a session. The connection is opened lazily on the first query
+    /// and re-established if it breaks.
+    ///
+    /// The backend is picked automatically, or forced by `ACTIVE_WIN_BACKEND`, see [`Backend`].
+    pub fn new() -> Self {
+        Self::with_backend(Backend::Auto)
+    }
+
+    /// Returns a builder to configure the session, e.g. to force a backend.
+    pub fn builder() -> ActiveWindowSessionBuilder {
+        ActiveWindowSessionBuilder::default()
+    }
+
+    fn with_backend(backend: Backend) -> Self {
+        Self {
+            api: BackendChain::new(backend),
+        }
+    }
+
+    pub fn get_position(&self) -> Result<WindowPosition, Error> {
+        self.api.get_position()
+    }
+
+    pub fn get_active_window(&self) -> Result<ActiveWindow, Error> {
+        self.api.get_active_window()
+    }
+
+    pub fn list_windows(&self) -> Result<Vec<ActiveWindow>, Error> {
+        self.api.list_windows()
+    }
+
+    pub fn list_monitors(&self) -> Result<Vec<Monitor>, Error> {
+        self.api.list_monitors()
+    }
+
+    pub fn workspaces(&self) -> Result<Vec<Workspace>, Error> {
+        self.api.workspaces()
+    }
+
+    pub fn watch_active_window(&self) -> Result<ActiveWindowWatcher, Error> {
+        Ok(ActiveWindowWatcher::new(self.api.watch()?))
+    }
+
+    #[cfg(feature = "async")]
+    pub fn active_window_stream(&self) -> Result<ActiveWindowStream, Error> {
+        Ok(ActiveWindowStream::new(self.api.watch()?))
+    }
+
+    pub fn idle_time(&self) -> Result<Duration, Error> {
+        self.api.idle_time()
+    }
+
+    pub fn get_window_icons(&self, window_id: &WindowId) -> Result<Vec<WindowIcon>, Error> {
+        self.api.get_window_icons(window_id)
+    }
+
+    pub fn capture_window(
+        &self,
+        window_id: &WindowId,
+        area: CaptureArea,
+    ) -> Result<Screenshot, Error> {
+        self.api.capture_window(window_id, area)
+    }
+
+    pub fn activate_window(&self, window_id: &WindowId) -> Result<(), Error> {
+        self.api.activate_window(window_id)
+    }
+
+    pub fn move_resize_window(
+        &self,
+        window_id: &WindowId,
+        position: &WindowPosition,
+    ) -> Result<(), Error> {
+        self.api.move_resize_window(window_id, position)
+    }
+
+    pub fn set_window_state(
+        &self,
+        window_id: &WindowId,
+        state: WindowState,
+        enable: bool,
+    ) -> Result<(), Error> {
+        self.api.set_window_state(window_id, state, enable)
+    }
+
+    pub fn close_window(&self, window_id: &WindowId) -> Result<(), Error> {
+        self.api.close_window(window_id)
+    }
+}
+
+impl Default for ActiveWindowSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Configures an [`ActiveWindowSession`], see [`ActiveWindowSession::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct ActiveWindowSessionBuilder {
+    backend: Backend,
+}
+
+impl ActiveWindowSessionBuilder {
+    /// Forces `backend` instead of picking one automatically. Its errors are returned as is,
+    /// without falling back to another backend, and `ACTIVE_WIN_BACKEND` is ignored.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Fails with [`Error::BackendUnavailable`] if the backend is not available on this platform.
+    pub fn build(self) -> Result<ActiveWindowSession, Error> {
+        Ok(ActiveWindowSession::with_backend(
+            self.backend.check_available()?,
+        ))
+    }
+}
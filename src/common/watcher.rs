@@ -0,0 +1,128 @@
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::active_window::ActiveWindow;
+use super::error::Error;
+
+/// Wakes up a blocked [`WindowEvents::next_change`], see [`WindowEvents::waker`].
+pub type WakeFn = Box<dyn Fn() + Send + Sync>;
+
+/// Source of active window changes driven by the platform's event mechanism,
+/// returned by [`PlatformApi::watch`](crate::PlatformApi::watch).
+pub trait WindowEvents: Send {
+    /// Blocks until the active window or its title changes, the first call reports the
+    /// window that is active when watching starts.
+    /// Returns `None` once `stop` has been triggered or the source is exhausted.
+    fn next_change(&mut self, stop: &StopHandle) -> Option<Result<ActiveWindow, Error>>;
+
+    /// Called from [`StopHandle::stop`] to interrupt a blocked `next_change`.
+    fn waker(&self) -> WakeFn;
+
+    /// Non-blocking counterpart of `next_change`, registers `cx` to be woken
+    /// once the underlying event source has something to read.
+    ///
+    /// The default yields [`Error::Unsupported`], for sources that can only block.
+    /// [`ActiveWindowStream`] ends after that item.
+    fn poll_change(&mut self, _cx: &mut Context<'_>) -> Poll<Option<Result<ActiveWindow, Error>>> {
+        Poll::Ready(Some(Err(Error::Unsupported)))
+    }
+}
+
+/// Stops an [`ActiveWindowWatcher`] from another thread.
+#[derive(Clone)]
+pub struct StopHandle {
+    stopped: Arc<AtomicBool>,
+    wake: Arc<WakeFn>,
+}
+
+impl StopHandle {
+    /// Ends the watch: a blocked [`Iterator::next`] returns `None` promptly.
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        (self.wake)();
+    }
+
+    /// Whether [`stop`](StopHandle::stop) has been called.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+}
+
+/// Blocking iterator over active window changes, see [`watch_active_window`](crate::watch_active_window).
+///
+/// The first item is the window that is active when watching starts. After that an item
+/// is produced every time another window gets focus or the focused window changes its title.
+/// Failures (e.g. [`Error::NoActiveWindow`] when focus moves to the desktop) are yielded as
+/// `Err` items without ending the iteration.
+pub struct ActiveWindowWatcher {
+    events: Box<dyn WindowEvents>,
+    stop: StopHandle,
+}
+
+impl ActiveWindowWatcher {
+    pub(crate) fn new(events: Box<dyn WindowEvents>) -> Self {
+        let stop = StopHandle {
+            stopped: Arc::new(AtomicBool::new(false)),
+            wake: Arc::new(events.waker()),
+        };
+
+        Self { events, stop }
+    }
+
+    /// Returns a handle that can stop this watcher from another thread.
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop.clone()
+    }
+}
+
+impl Iterator for ActiveWindowWatcher {
+    type Item = Result<ActiveWindow, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.stop.is_stopped() {
+            return None;
+        }
+
+        self.events.next_change(&self.stop)
+    }
+}
+
+/// Asynchronous stream of active window changes, see [`active_window_stream`](crate::active_window_stream).
+///
+/// Yields the same items as [`ActiveWindowWatcher`] but waits on the display server connection
+/// through the [`async-io`](https://docs.rs/async-io) reactor, so it can be awaited from tokio,
+/// async-std or any other executor. Dropping the stream stops watching.
+#[cfg(feature = "async")]
+pub struct ActiveWindowStream {
+    events: Box<dyn WindowEvents>,
+    // Set once the source turned out not to support polling
+    unsupported: bool,
+}
+
+#[cfg(feature = "async")]
+impl ActiveWindowStream {
+    pub(crate) fn new(events: Box<dyn WindowEvents>) -> Self {
+        Self {
+            events,
+            unsupported: false,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl futures_core::Stream for ActiveWindowStream {
+    type Item = Result<ActiveWindow, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.unsupported {
+            return Poll::Ready(None);
+        }
+
+        let change = self.events.poll_change(cx);
+        self.unsupported = matches!(change, Poll::Ready(Some(Err(Error::Unsupported))));
+        change
+    }
+}
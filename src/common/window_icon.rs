@@ -0,0 +1,26 @@
+/// One size of a window's icon as 8-bit RGBA pixels, see
+/// [`get_window_icons`](crate::get_window_icons).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowIcon {
+    pub width: u32,
+    pub height: u32,
+    /// Rows from top to bottom, four bytes per pixel, not premultiplied.
+    pub rgba: Vec<u8>,
+}
+
+impl WindowIcon {
+    /// Picks the icon whose larger side is closest to `size` pixels, preferring the larger
+    /// icon on a tie since scaling down looks better than scaling up.
+    pub fn closest(icons: &[WindowIcon], size: u32) -> Option<&WindowIcon> {
+        icons.iter().min_by_key(|icon| {
+            let side = icon.width.max(icon.height);
+            (side.abs_diff(size), std::cmp::Reverse(side))
+        })
+    }
+
+    /// Encodes the icon as PNG, available with the `png` feature.
+    #[cfg(feature = "png")]
+    pub fn to_png(&self) -> std::io::Result<Vec<u8>> {
+        super::png::encode_rgba(self.width, self.height, &self.rgba)
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::error::Error;
+
+/// Identifies a window in the window system it was read from.
+///
+/// [`Display`](fmt::Display) produces the same string `window_id` held before it was typed,
+/// and [`FromStr`] parses that string back:
+///
+/// | Variant             | String                                   |
+/// |---------------------|------------------------------------------|
+/// | `X11(81788931)`     | `81788931`                               |
+/// | `KWin(..)`          | `{6e0d6a3d-95a6-4c89-9b1b-2f3a0f4f2b6e}` |
+/// | `Windows(9700584)`  | `HWND(9700584)`                          |
+/// | `MacOS(1234)`       | `1234`                                   |
+///
+/// Plain numbers parse as `MacOS` on macOS and as `X11` everywhere else.
+/// With the `serde` feature enabled the id is serialized as this string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub enum WindowId {
+    /// X11 window XID.
+    X11(u32),
+    /// KWin `internalId` of a window on KDE Plasma (Wayland), a UUID in braces.
+    KWin(String),
+    /// Windows `HWND` handle value.
+    Windows(isize),
+    /// macOS `kCGWindowNumber`.
+    MacOS(u32),
+}
+
+impl Default for WindowId {
+    /// The null window id of the current platform.
+    fn default() -> Self {
+        if cfg!(target_os = "windows") {
+            WindowId::Windows(0)
+        } else if cfg!(target_os = "macos") {
+            WindowId::MacOS(0)
+        } else {
+            WindowId::X11(0)
+        }
+    }
+}
+
+impl fmt::Display for WindowId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowId::X11(xid) => write!(f, "{}", xid),
+            WindowId::KWin(id) => f.write_str(id),
+            WindowId::Windows(hwnd) => write!(f, "HWND({})", hwnd),
+            WindowId::MacOS(number) => write!(f, "{}", number),
+        }
+    }
+}
+
+impl FromStr for WindowId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidWindowId(s.to_owned());
+
+        if let Some(hwnd) = s.strip_prefix("HWND(").and_then(|s| s.strip_suffix(')')) {
+            return hwnd.parse().map(WindowId::Windows).map_err(|_| invalid());
+        }
+
+        if s.len() > 2 && s.starts_with('{') && s.ends_with('}') {
+            return Ok(WindowId::KWin(s.to_owned()));
+        }
+
+        let number = s.parse().map_err(|_| invalid())?;
+        if cfg!(target_os = "macos") {
+            Ok(WindowId::MacOS(number))
+        } else {
+            Ok(WindowId::X11(number))
+        }
+    }
+}
+
+impl From<WindowId> for String {
+    fn from(id: WindowId) -> Self {
+        id.to_string()
+    }
+}
+
+impl TryFrom<String> for WindowId {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
@@ -1,4 +1,25 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::error::Error;
+
+/// Unit of the coordinates in a [`WindowPosition`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum CoordinateSpace {
+    /// Device pixels, as reported by X11 and Windows.
+    #[default]
+    Physical,
+    /// Pixels divided by the monitor's scale factor, as reported by KWin and macOS (points).
+    Logical,
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct WindowPosition {
     pub x: f64,
     pub y: f64,
@@ -15,6 +36,46 @@ impl WindowPosition {
             height: h,
         }
     }
+
+    /// Converts logical coordinates to physical pixels.
+    pub fn to_physical(&self, scale_factor: f64) -> WindowPosition {
+        WindowPosition::new(
+            self.x * scale_factor,
+            self.y * scale_factor,
+            self.width * scale_factor,
+            self.height * scale_factor,
+        )
+    }
+
+    /// Converts physical pixels to logical coordinates.
+    pub fn to_logical(&self, scale_factor: f64) -> WindowPosition {
+        self.to_physical(1.0 / scale_factor)
+    }
+
+    // Rejects what no window system takes as a geometry, instead of letting `as` casts
+    // saturate it or formatting `NaN` into a script
+    pub(crate) fn check_geometry(&self) -> Result<(), Error> {
+        let limit = f64::from(i32::MAX);
+        let in_range = [self.x, self.y, self.width, self.height]
+            .iter()
+            .all(|value| value.is_finite() && value.abs() <= limit);
+
+        if in_range && self.width >= 0.0 && self.height >= 0.0 {
+            Ok(())
+        } else {
+            Err(Error::InvalidInput(format!(
+                "window geometry {}x{} at {}, {}",
+                self.width, self.height, self.x, self.y
+            )))
+        }
+    }
+
+    pub(crate) fn intersection_area(&self, other: &WindowPosition) -> f64 {
+        let width = (self.x + self.width).min(other.x + other.width) - self.x.max(other.x);
+        let height = (self.y + self.height).min(other.y + other.height) - self.y.max(other.y);
+
+        width.max(0.0) * height.max(0.0)
+    }
 }
 
 impl Default for WindowPosition {
@@ -22,3 +83,30 @@ impl Default for WindowPosition {
         Self::new(0.0, 0.0, 0.0, 0.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_geometry() {
+        assert!(WindowPosition::new(-100.0, 20.0, 800.0, 600.0)
+            .check_geometry()
+            .is_ok());
+        assert!(WindowPosition::new(0.0, 0.0, 0.0, 0.0)
+            .check_geometry()
+            .is_ok());
+
+        for invalid in [
+            WindowPosition::new(f64::NAN, 0.0, 800.0, 600.0),
+            WindowPosition::new(0.0, f64::INFINITY, 800.0, 600.0),
+            WindowPosition::new(0.0, 0.0, -1.0, 600.0),
+            WindowPosition::new(0.0, 0.0, 800.0, 1e12),
+        ] {
+            assert!(matches!(
+                invalid.check_geometry(),
+                Err(Error::InvalidInput(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,28 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+bitflags::bitflags! {
+    /// State of a window as managed by the window manager, from `_NET_WM_STATE` on X11
+    /// and the window's properties on KWin.
+    ///
+    /// With the `serde` feature enabled it is serialized as the flag names joined by `|`,
+    /// e.g. `"MAXIMIZED_VERT | MAXIMIZED_HORZ"`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+    pub struct WindowState: u32 {
+        /// Minimized (iconified), `_NET_WM_STATE_HIDDEN` on X11.
+        const MINIMIZED = 1 << 0;
+        const MAXIMIZED_VERT = 1 << 1;
+        const MAXIMIZED_HORZ = 1 << 2;
+        /// Maximized in both directions.
+        const MAXIMIZED = Self::MAXIMIZED_VERT.bits() | Self::MAXIMIZED_HORZ.bits();
+        const FULLSCREEN = 1 << 3;
+        /// Kept above other windows.
+        const ABOVE = 1 << 4;
+        /// Shown on every workspace.
+        const STICKY = 1 << 5;
+        const DEMANDS_ATTENTION = 1 << 6;
+        /// Modal dialog for its parent window.
+        const MODAL = 1 << 7;
+    }
+}
@@ -0,0 +1,40 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Kind of window, from `_NET_WM_WINDOW_TYPE` on X11 and the window's properties on KWin.
+///
+/// Windows that do not state a type are [`WindowType::Normal`], or [`WindowType::Dialog`]
+/// if they are transient for another window, as the EWMH specification prescribes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum WindowType {
+    /// Top-level application window.
+    #[default]
+    Normal,
+    Dialog,
+    /// Panel or dock, e.g. a taskbar.
+    Dock,
+    /// Desktop background window.
+    Desktop,
+    /// Toolbar or palette torn off from a main window.
+    Toolbar,
+    /// Pinnable menu torn off from a main window.
+    Menu,
+    /// Small persistent utility window, e.g. a palette or toolbox.
+    Utility,
+    /// Splash screen shown while an application starts.
+    Splash,
+    DropdownMenu,
+    PopupMenu,
+    Tooltip,
+    Notification,
+    /// Popup of a combo box.
+    Combo,
+    /// Icon dragged during drag and drop.
+    Dnd,
+}
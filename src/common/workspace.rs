@@ -0,0 +1,14 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A virtual desktop, see [`workspaces`](crate::workspaces).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct Workspace {
+    /// Zero-based position, as in `_NET_CURRENT_DESKTOP` on X11.
+    pub index: u32,
+    /// Name given to the workspace, empty if it has none.
+    pub name: String,
+    /// Whether this is the workspace currently shown.
+    pub is_current: bool,
+}
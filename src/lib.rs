@@ -2,6 +2,8 @@
 #[macro_use]
 extern crate objc;
 
+use std::time::Duration;
+
 mod common;
 #[cfg(target_os = "linux")]
 mod linux;
@@ -11,22 +13,147 @@ mod mac;
 mod win;
 
 #[cfg(target_os = "linux")]
-use linux::init_platform_api;
+use linux::builtin_backends;
 #[cfg(target_os = "macos")]
-use mac::init_platform_api;
+use mac::builtin_backends;
 #[cfg(target_os = "windows")]
-use win::init_platform_api;
+use win::builtin_backends;
 
 pub use common::active_window::ActiveWindow;
-use common::platform_api::PlatformApi;
-pub use common::window_position::WindowPosition;
+pub use common::backend::Backend;
+pub use common::desktop_entry::DesktopEntry;
+pub use common::error::Error;
+#[cfg(feature = "mock")]
+pub use common::mock::MockBackend;
+pub use common::monitor::Monitor;
+pub use common::packaging::{PackageFormat, Packaging};
+pub use common::platform_api::PlatformApi;
+pub use common::process_info::ProcessInfo;
+pub use common::registry::register_backend;
+use common::registry::BackendChain;
+pub use common::screenshot::{CaptureArea, Screenshot};
+pub use common::session::{ActiveWindowSession, ActiveWindowSessionBuilder};
+#[cfg(feature = "async")]
+pub use common::watcher::ActiveWindowStream;
+pub use common::watcher::{ActiveWindowWatcher, StopHandle, WakeFn, WindowEvents};
+pub use common::window_icon::WindowIcon;
+pub use common::window_id::WindowId;
+pub use common::window_position::{CoordinateSpace, WindowPosition};
+pub use common::window_state::WindowState;
+pub use common::window_type::WindowType;
+pub use common::workspace::Workspace;
 
-pub fn get_position() -> Result<WindowPosition, ()> {
-    let api = init_platform_api();
+pub fn get_position() -> Result<WindowPosition, Error> {
+    let api = BackendChain::new(Backend::Auto);
     api.get_position()
 }
 
-pub fn get_active_window() -> Result<ActiveWindow, ()> {
-    let api = init_platform_api();
+pub fn get_active_window() -> Result<ActiveWindow, Error> {
+    let api = BackendChain::new(Backend::Auto);
     api.get_active_window()
 }
+
+/// Lists the top-level windows managed by the window manager, with
+/// [`ActiveWindow::is_active`] set on the focused one.
+pub fn list_windows() -> Result<Vec<ActiveWindow>, Error> {
+    let api = BackendChain::new(Backend::Auto);
+    api.list_windows()
+}
+
+/// Lists the monitors attached to the display, e.g. to find the screen a window is on.
+///
+/// Currently supported on X11 through RandR, including XWayland on Wayland sessions, and on
+/// KDE Plasma (Wayland), where only the name, geometry and scale factor of each output are known.
+pub fn list_monitors() -> Result<Vec<Monitor>, Error> {
+    let api = BackendChain::new(Backend::Auto);
+    api.list_monitors()
+}
+
+/// Lists the virtual desktops (workspaces), with [`Workspace::is_current`] set on the one shown.
+///
+/// Currently supported on X11 through EWMH and on KDE Plasma (Wayland).
+pub fn workspaces() -> Result<Vec<Workspace>, Error> {
+    let api = BackendChain::new(Backend::Auto);
+    api.workspaces()
+}
+
+/// Watches the active window instead of polling it, see [`ActiveWindowWatcher`].
+///
+/// Currently supported on X11, where it listens for `_NET_ACTIVE_WINDOW` and title changes,
+/// and on KDE Plasma (Wayland), where a KWin script reports window activations.
+pub fn watch_active_window() -> Result<ActiveWindowWatcher, Error> {
+    let api = BackendChain::new(Backend::Auto);
+    Ok(ActiveWindowWatcher::new(api.watch()?))
+}
+
+/// Async version of [`watch_active_window`], see [`ActiveWindowStream`].
+#[cfg(feature = "async")]
+pub fn active_window_stream() -> Result<ActiveWindowStream, Error> {
+    let api = BackendChain::new(Backend::Auto);
+    Ok(ActiveWindowStream::new(api.watch()?))
+}
+
+/// Time since the user last used the keyboard or mouse, to tell whether anyone is at the
+/// computer while a window is active.
+///
+/// Currently supported on X11 through the MIT-SCREEN-SAVER extension. Under XWayland only input
+/// that went to X11 windows is counted.
+pub fn idle_time() -> Result<Duration, Error> {
+    let api = BackendChain::new(Backend::Auto);
+    api.idle_time()
+}
+
+/// Returns every size of a window's icon as RGBA images, use [`WindowIcon::closest`] to pick
+/// one for the size it is shown at.
+///
+/// Currently supported on X11, from `_NET_WM_ICON`.
+pub fn get_window_icons(window_id: &WindowId) -> Result<Vec<WindowIcon>, Error> {
+    let api = BackendChain::new(Backend::Auto);
+    api.get_window_icons(window_id)
+}
+
+/// Takes a screenshot of a window, of its whole frame or only its content.
+///
+/// The image shows what is on screen in the window's area, including other windows covering it,
+/// and is cut off at the screen edges. Currently supported on X11, through MIT-SHM when the
+/// X server is local and `GetImage` otherwise.
+pub fn capture_window(window_id: &WindowId, area: CaptureArea) -> Result<Screenshot, Error> {
+    let api = BackendChain::new(Backend::Auto);
+    api.capture_window(window_id, area)
+}
+
+/// Focuses and raises the window with the given id, restoring it if it is minimized.
+///
+/// Window control is supported on X11 through EWMH client messages and on KDE Plasma (Wayland).
+pub fn activate_window(window_id: &WindowId) -> Result<(), Error> {
+    let api = BackendChain::new(Backend::Auto);
+    api.activate_window(window_id)
+}
+
+/// Moves and resizes a window, `position` is in the coordinate space its
+/// [`ActiveWindow::coordinate_space`] reports.
+///
+/// `position` means the same as [`ActiveWindow::position`] of the backend: the client area on
+/// X11 and the frame on KDE Plasma, so passing a window's own position leaves it in place.
+/// Fails with [`Error::InvalidInput`] for values that are not finite or a negative size.
+pub fn move_resize_window(window_id: &WindowId, position: &WindowPosition) -> Result<(), Error> {
+    let api = BackendChain::new(Backend::Auto);
+    api.move_resize_window(window_id, position)
+}
+
+/// Sets (`enable`) or clears the flags in `state` on a window, e.g. [`WindowState::MINIMIZED`]
+/// to minimize it.
+pub fn set_window_state(
+    window_id: &WindowId,
+    state: WindowState,
+    enable: bool,
+) -> Result<(), Error> {
+    let api = BackendChain::new(Backend::Auto);
+    api.set_window_state(window_id, state, enable)
+}
+
+/// Asks a window to close, as if its close button was clicked.
+pub fn close_window(window_id: &WindowId) -> Result<(), Error> {
+    let api = BackendChain::new(Backend::Auto);
+    api.close_window(window_id)
+}
@@ -0,0 +1,331 @@
+use std::ptr;
+
+use xcb::{shm, x};
+
+use super::connection::X11Connection;
+use super::control::xcb_window;
+use super::platform_api::get_xcb_window;
+use crate::{CaptureArea, Error, Screenshot, WindowId, WindowPosition};
+
+// Layout of the root window's pixels, only 24 and 32 bits per pixel with 8-bit channels are handled
+struct PixelFormat {
+    bytes_per_pixel: usize,
+    // Rows are padded to a multiple of this many bytes
+    scanline_pad: usize,
+    red_mask: u32,
+    green_mask: u32,
+    blue_mask: u32,
+    lsb_first: bool,
+}
+
+impl PixelFormat {
+    fn of_root(conn: &X11Connection) -> Result<Self, Error> {
+        let setup = conn.get_setup();
+        let screen = setup
+            .roots()
+            .find(|screen| screen.root() == conn.root)
+            .ok_or(Error::NoDisplayServer)?;
+
+        let format = setup
+            .pixmap_formats()
+            .iter()
+            .find(|format| format.depth() == screen.root_depth());
+        let visual = screen
+            .allowed_depths()
+            .flat_map(|depth| depth.visuals().iter())
+            .find(|visual| visual.visual_id() == screen.root_visual());
+
+        match (format, visual) {
+            (Some(format), Some(visual)) if matches!(format.bits_per_pixel(), 24 | 32) => {
+                Ok(Self {
+                    bytes_per_pixel: usize::from(format.bits_per_pixel() / 8),
+                    scanline_pad: usize::from(format.scanline_pad() / 8).max(1),
+                    red_mask: visual.red_mask(),
+                    green_mask: visual.green_mask(),
+                    blue_mask: visual.blue_mask(),
+                    lsb_first: setup.image_byte_order() == x::ImageOrder::LsbFirst,
+                })
+            }
+            _ => Err(Error::Unsupported),
+        }
+    }
+
+    fn stride(&self, width: u16) -> usize {
+        (usize::from(width) * self.bytes_per_pixel).next_multiple_of(self.scanline_pad)
+    }
+
+    fn to_rgba(&self, data: &[u8], width: u16) -> Vec<u8> {
+        let channel = |pixel: u32, mask: u32| ((pixel & mask) >> mask.trailing_zeros()) as u8;
+        let row_len = usize::from(width) * self.bytes_per_pixel;
+
+        data.chunks(self.stride(width))
+            .filter_map(|row| row.get(..row_len))
+            .flat_map(|row| row.chunks_exact(self.bytes_per_pixel))
+            .flat_map(|bytes| {
+                let pixel = bytes.iter().enumerate().fold(0, |pixel, (index, byte)| {
+                    let shift = if self.lsb_first {
+                        index
+                    } else {
+                        bytes.len() - 1 - index
+                    };
+                    pixel | u32::from(*byte) << (shift * 8)
+                });
+
+                [
+                    channel(pixel, self.red_mask),
+                    channel(pixel, self.green_mask),
+                    channel(pixel, self.blue_mask),
+                    u8::MAX,
+                ]
+            })
+            .collect()
+    }
+}
+
+// Screen area as the root window coordinates GetImage takes
+#[derive(Debug, PartialEq)]
+struct Area {
+    x: i16,
+    y: i16,
+    width: u16,
+    height: u16,
+}
+
+impl Area {
+    // Cuts off what lies outside the screen
+    fn clipped(position: &WindowPosition, screen_width: u16, screen_height: u16) -> Self {
+        let left = position.x.round().max(0.0);
+        let top = position.y.round().max(0.0);
+        let right = (position.x + position.width)
+            .round()
+            .min(screen_width.into());
+        let bottom = (position.y + position.height)
+            .round()
+            .min(screen_height.into());
+
+        Self {
+            x: left as i16,
+            y: top as i16,
+            width: (right - left).max(0.0) as u16,
+            height: (bottom - top).max(0.0) as u16,
+        }
+    }
+
+    fn position(&self) -> WindowPosition {
+        WindowPosition::new(
+            self.x.into(),
+            self.y.into(),
+            self.width.into(),
+            self.height.into(),
+        )
+    }
+}
+
+// SysV shared memory segment the X server writes the image into
+struct SharedMemory {
+    id: libc::c_int,
+    addr: *mut libc::c_void,
+    size: usize,
+}
+
+impl SharedMemory {
+    fn new(size: usize) -> Option<Self> {
+        let id = unsafe { libc::shmget(libc::IPC_PRIVATE, size, libc::IPC_CREAT | 0o600) };
+        if id < 0 {
+            return None;
+        }
+
+        let addr = unsafe { libc::shmat(id, ptr::null(), 0) };
+        let memory = Self { id, addr, size };
+        // A failed attach returns -1, see shmat(2)
+        (addr as isize != -1).then_some(memory)
+    }
+
+    fn data(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.addr as *const u8, self.size) }
+    }
+}
+
+impl Drop for SharedMemory {
+    fn drop(&mut self) {
+        unsafe {
+            if self.addr as isize != -1 {
+                libc::shmdt(self.addr);
+            }
+            // The segment goes away once the X server has detached as well
+            libc::shmctl(self.id, libc::IPC_RMID, ptr::null_mut());
+        }
+    }
+}
+
+// Avoids sending the whole image through the socket, fails if the X server is remote
+fn get_xcb_image_shm(
+    conn: &X11Connection,
+    area: &Area,
+    format: &PixelFormat,
+) -> Result<Vec<u8>, Error> {
+    let size = format.stride(area.width) * usize::from(area.height);
+    let memory = SharedMemory::new(size).ok_or(Error::Unsupported)?;
+
+    let shmseg: shm::Seg = conn.generate_id();
+    conn.send_and_check_request(&shm::Attach {
+        shmseg,
+        shmid: memory.id as u32,
+        read_only: false,
+    })?;
+
+    let image = conn.wait_for_reply(conn.send_request(&shm::GetImage {
+        drawable: x::Drawable::Window(conn.root),
+        x: area.x,
+        y: area.y,
+        width: area.width,
+        height: area.height,
+        plane_mask: u32::MAX,
+        format: x::ImageFormat::ZPixmap as u8,
+        shmseg,
+        offset: 0,
+    }));
+    let data = image.map(|image| memory.data()[..(image.size() as usize).min(size)].to_vec());
+
+    conn.send_and_check_request(&shm::Detach { shmseg })?;
+    Ok(data?)
+}
+
+fn get_xcb_image(conn: &X11Connection, area: &Area) -> Result<Vec<u8>, Error> {
+    let image = conn.wait_for_reply(conn.send_request(&x::GetImage {
+        format: x::ImageFormat::ZPixmap,
+        drawable: x::Drawable::Window(conn.root),
+        x: area.x,
+        y: area.y,
+        width: area.width,
+        height: area.height,
+        plane_mask: u32::MAX,
+    }))?;
+
+    Ok(image.data().to_vec())
+}
+
+// Copies the window's area from the root window, so it shows what is on screen there,
+// including windows overlapping it
+pub fn capture_xcb_window(
+    conn: &X11Connection,
+    window_id: &WindowId,
+    area: CaptureArea,
+) -> Result<Screenshot, Error> {
+    let window = get_xcb_window(conn, xcb_window(conn, window_id)?)?;
+    let format = PixelFormat::of_root(conn)?;
+
+    let screen = conn.wait_for_reply(conn.send_request(&x::GetGeometry {
+        drawable: x::Drawable::Window(conn.root),
+    }))?;
+
+    let area = Area::clipped(
+        match area {
+            CaptureArea::Frame => &window.frame_rect,
+            CaptureArea::Client => &window.client_rect,
+        },
+        screen.width(),
+        screen.height(),
+    );
+    let data = if area.width == 0 || area.height == 0 {
+        Vec::new()
+    } else if conn.has_shm {
+        get_xcb_image_shm(conn, &area, &format).or_else(|_| get_xcb_image(conn, &area))?
+    } else {
+        get_xcb_image(conn, &area)?
+    };
+
+    Ok(Screenshot {
+        position: area.position(),
+        width: area.width.into(),
+        height: area.height.into(),
+        rgba: format.to_rgba(&data, area.width),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(bytes_per_pixel: usize, lsb_first: bool) -> PixelFormat {
+        PixelFormat {
+            bytes_per_pixel,
+            scanline_pad: 4,
+            red_mask: 0xff_0000,
+            green_mask: 0x00_ff00,
+            blue_mask: 0x00_00ff,
+            lsb_first,
+        }
+    }
+
+    #[test]
+    fn converts_32_bit_pixels() {
+        let red_green = [0x00, 0x00, 0xff, 0x00, 0x00, 0x80, 0x00, 0x00];
+        assert_eq!(
+            format(4, true).to_rgba(&red_green, 2),
+            [0xff, 0, 0, 0xff, 0, 0x80, 0, 0xff]
+        );
+
+        let red_green = [0x00, 0xff, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00];
+        assert_eq!(
+            format(4, false).to_rgba(&red_green, 2),
+            [0xff, 0, 0, 0xff, 0, 0x80, 0, 0xff]
+        );
+    }
+
+    #[test]
+    fn converts_24_bit_pixels_in_padded_rows() {
+        // Two rows of a single pixel, each padded from 3 to 4 bytes
+        let lsb = [0x10, 0x20, 0x30, 0xee, 0x00, 0x00, 0xff, 0xee];
+        assert_eq!(
+            format(3, true).to_rgba(&lsb, 1),
+            [0x30, 0x20, 0x10, 0xff, 0xff, 0, 0, 0xff]
+        );
+
+        let msb = [0x30, 0x20, 0x10, 0xee, 0xff, 0x00, 0x00, 0xee];
+        assert_eq!(
+            format(3, false).to_rgba(&msb, 1),
+            [0x30, 0x20, 0x10, 0xff, 0xff, 0, 0, 0xff]
+        );
+    }
+
+    #[test]
+    fn clips_to_the_screen() {
+        let clipped = |x, y, width, height| {
+            Area::clipped(&WindowPosition::new(x, y, width, height), 1920, 1080)
+        };
+
+        assert_eq!(
+            clipped(100.0, 50.0, 800.0, 600.0),
+            Area {
+                x: 100,
+                y: 50,
+                width: 800,
+                height: 600,
+            }
+        );
+        // Partly left of and above the screen
+        assert_eq!(
+            clipped(-100.0, -20.0, 800.0, 600.0),
+            Area {
+                x: 0,
+                y: 0,
+                width: 700,
+                height: 580,
+            }
+        );
+        // Partly right of and below the screen
+        assert_eq!(
+            clipped(1800.0, 1000.0, 800.0, 600.0),
+            Area {
+                x: 1800,
+                y: 1000,
+                width: 120,
+                height: 80,
+            }
+        );
+        // Entirely off screen
+        assert_eq!(clipped(-900.0, 0.0, 800.0, 600.0).width, 0);
+        assert_eq!(clipped(2000.0, 1200.0, 800.0, 600.0).width, 0);
+    }
+}
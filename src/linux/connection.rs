@@ -0,0 +1,165 @@
+use std::cell::RefCell;
+use std::ops::Deref;
+
+use xcb::{randr, x};
+
+use crate::{Error, Monitor};
+
+xcb::atoms_struct! {
+    #[derive(Copy, Clone, Debug)]
+    pub struct Atoms {
+        // Interned only if they exist, a missing atom means the WM does not support EWMH
+        pub net_active_window => b"_NET_ACTIVE_WINDOW",
+        pub net_client_list => b"_NET_CLIENT_LIST",
+        pub net_moveresize_window => b"_NET_MOVERESIZE_WINDOW",
+        pub net_close_window => b"_NET_CLOSE_WINDOW",
+        pub wm_change_state => b"WM_CHANGE_STATE" only_if_exists = false,
+        pub net_wm_pid => b"_NET_WM_PID" only_if_exists = false,
+        pub net_wm_name => b"_NET_WM_NAME" only_if_exists = false,
+        pub net_wm_state => b"_NET_WM_STATE" only_if_exists = false,
+        pub net_wm_window_type => b"_NET_WM_WINDOW_TYPE" only_if_exists = false,
+        pub net_wm_desktop => b"_NET_WM_DESKTOP" only_if_exists = false,
+        pub net_frame_extents => b"_NET_FRAME_EXTENTS" only_if_exists = false,
+        pub net_wm_icon => b"_NET_WM_ICON" only_if_exists = false,
+        pub gtk_application_id => b"_GTK_APPLICATION_ID" only_if_exists = false,
+        pub wm_window_role => b"WM_WINDOW_ROLE" only_if_exists = false,
+        pub net_number_of_desktops => b"_NET_NUMBER_OF_DESKTOPS" only_if_exists = false,
+        pub net_current_desktop => b"_NET_CURRENT_DESKTOP" only_if_exists = false,
+        pub net_desktop_names => b"_NET_DESKTOP_NAMES" only_if_exists = false,
+        // No window can have a state or type the WM never interned
+        pub net_wm_state_hidden => b"_NET_WM_STATE_HIDDEN",
+        pub net_wm_state_maximized_vert => b"_NET_WM_STATE_MAXIMIZED_VERT",
+        pub net_wm_state_maximized_horz => b"_NET_WM_STATE_MAXIMIZED_HORZ",
+        pub net_wm_state_fullscreen => b"_NET_WM_STATE_FULLSCREEN",
+        pub net_wm_state_above => b"_NET_WM_STATE_ABOVE",
+        pub net_wm_state_sticky => b"_NET_WM_STATE_STICKY",
+        pub net_wm_state_demands_attention => b"_NET_WM_STATE_DEMANDS_ATTENTION",
+        pub net_wm_state_modal => b"_NET_WM_STATE_MODAL",
+        pub net_wm_window_type_normal => b"_NET_WM_WINDOW_TYPE_NORMAL",
+        pub net_wm_window_type_dialog => b"_NET_WM_WINDOW_TYPE_DIALOG",
+        pub net_wm_window_type_dock => b"_NET_WM_WINDOW_TYPE_DOCK",
+        pub net_wm_window_type_desktop => b"_NET_WM_WINDOW_TYPE_DESKTOP",
+        pub net_wm_window_type_toolbar => b"_NET_WM_WINDOW_TYPE_TOOLBAR",
+        pub net_wm_window_type_menu => b"_NET_WM_WINDOW_TYPE_MENU",
+        pub net_wm_window_type_utility => b"_NET_WM_WINDOW_TYPE_UTILITY",
+        pub net_wm_window_type_splash => b"_NET_WM_WINDOW_TYPE_SPLASH",
+        pub net_wm_window_type_dropdown_menu => b"_NET_WM_WINDOW_TYPE_DROPDOWN_MENU",
+        pub net_wm_window_type_popup_menu => b"_NET_WM_WINDOW_TYPE_POPUP_MENU",
+        pub net_wm_window_type_tooltip => b"_NET_WM_WINDOW_TYPE_TOOLTIP",
+        pub net_wm_window_type_notification => b"_NET_WM_WINDOW_TYPE_NOTIFICATION",
+        pub net_wm_window_type_combo => b"_NET_WM_WINDOW_TYPE_COMBO",
+        pub net_wm_window_type_dnd => b"_NET_WM_WINDOW_TYPE_DND",
+    }
+}
+
+// X11 connection kept alive between queries, together with the atoms it needs
+pub struct X11Connection {
+    conn: xcb::Connection,
+    pub root: x::Window,
+    pub atoms: Atoms,
+    pub has_randr: bool,
+    pub has_shm: bool,
+    pub has_screensaver: bool,
+    // Filled by `list_xcb_monitors`, dropped when the screen configuration changes, see `connect`
+    monitors: RefCell<Option<Vec<Monitor>>>,
+}
+
+impl X11Connection {
+    pub fn connect() -> Result<Self, Error> {
+        let (conn, screen_num) = xcb::Connection::connect_with_extensions(
+            None,
+            &[],
+            &[
+                xcb::Extension::RandR,
+                xcb::Extension::Shm,
+                xcb::Extension::ScreenSaver,
+            ],
+        )?;
+
+        let root = conn
+            .get_setup()
+            .roots()
+            .nth(screen_num.try_into().unwrap_or(0))
+            .ok_or(Error::NoDisplayServer)?
+            .root();
+        let atoms = Atoms::intern_all(&conn)?;
+        let has_extension = |name| conn.active_extensions().any(|extension| extension == name);
+        let has_randr = has_extension(xcb::Extension::RandR);
+        let has_shm = has_extension(xcb::Extension::Shm);
+        let has_screensaver = has_extension(xcb::Extension::ScreenSaver);
+
+        // Events that invalidate the monitor cache, see `handle_event`. PropertyNotify for
+        // RESOURCE_MANAGER would take every other root property change with it, so a new
+        // Xft.dpi only shows up on the next screen change or `list_xcb_monitors`, unless the
+        // connection watches the root window anyway.
+        if has_randr {
+            conn.send_request(&randr::SelectInput {
+                window: root,
+                enable: randr::NotifyMask::SCREEN_CHANGE
+                    | randr::NotifyMask::CRTC_CHANGE
+                    | randr::NotifyMask::OUTPUT_CHANGE,
+            });
+        }
+
+        Ok(Self {
+            conn,
+            root,
+            atoms,
+            has_randr,
+            has_shm,
+            has_screensaver,
+            monitors: RefCell::new(None),
+        })
+    }
+
+    pub fn cached_monitors(&self) -> Option<Vec<Monitor>> {
+        self.monitors.borrow().clone()
+    }
+
+    pub fn cache_monitors(&self, monitors: &[Monitor]) {
+        *self.monitors.borrow_mut() = Some(monitors.to_vec());
+    }
+
+    // Keeps the caches up to date, must see every event read from the connection
+    pub fn handle_event(&self, event: &xcb::Event) {
+        let invalidates_monitors = match event {
+            xcb::Event::RandR(randr::Event::ScreenChangeNotify(_) | randr::Event::Notify(_)) => {
+                true
+            }
+            // The scale factor comes from Xft.dpi
+            xcb::Event::X(x::Event::PropertyNotify(event)) => {
+                event.window() == self.root && event.atom() == x::ATOM_RESOURCE_MANAGER
+            }
+            _ => false,
+        };
+
+        if invalidates_monitors {
+            self.monitors.take();
+        }
+    }
+
+    // For connections that are only used for queries: handles the events queued since the last one
+    pub fn handle_queued_events(&self) {
+        loop {
+            match self.conn.poll_for_event() {
+                Ok(Some(event)) => self.handle_event(&event),
+                Ok(None) => return,
+                // Errors of requests whose replies were not checked
+                Err(xcb::Error::Protocol(_)) => continue,
+                Err(_) => return,
+            }
+        }
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.conn.has_error().is_err()
+    }
+}
+
+impl Deref for X11Connection {
+    type Target = xcb::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.conn
+    }
+}
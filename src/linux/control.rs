@@ -0,0 +1,187 @@
+use xcb::{x, Xid, XidNew};
+
+use super::connection::X11Connection;
+use crate::{Error, WindowId, WindowPosition, WindowState};
+
+// Tells the WM the request comes from a pager or taskbar, so it is not refused as focus stealing
+const SOURCE_PAGER: u32 = 2;
+
+const NET_WM_STATE_REMOVE: u32 = 0;
+const NET_WM_STATE_ADD: u32 = 1;
+
+const STATIC_GRAVITY: u32 = 10;
+
+// ICCCM state requested through WM_CHANGE_STATE to minimize a window
+const ICONIC_STATE: u32 = 3;
+
+// Checks that the id is an X11 window that still exists
+pub fn xcb_window(conn: &X11Connection, window_id: &WindowId) -> Result<x::Window, Error> {
+    let WindowId::X11(xid) = window_id else {
+        return Err(Error::InvalidWindowId(window_id.to_string()));
+    };
+    let window = x::Window::new(*xid);
+
+    let cookie = conn.send_request(&x::GetWindowAttributes { window });
+    match conn.wait_for_reply(cookie) {
+        Ok(_) => Ok(window),
+        Err(xcb::Error::Protocol(xcb::ProtocolError::X(x::Error::Window(_), _))) => {
+            Err(Error::WindowNotFound(window_id.clone()))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+// Sends a request to the WM as described by EWMH, as a client message to the root window
+fn send_xcb_client_message(
+    conn: &X11Connection,
+    window: x::Window,
+    message_type: x::Atom,
+    data: [u32; 5],
+) -> Result<(), Error> {
+    if message_type == x::ATOM_NONE {
+        return Err(Error::EwmhUnsupported);
+    }
+
+    let event =
+        x::ClientMessageEvent::new(window, message_type, x::ClientMessageData::Data32(data));
+    conn.send_and_check_request(&x::SendEvent {
+        propagate: false,
+        destination: x::SendEventDest::Window(conn.root),
+        event_mask: x::EventMask::SUBSTRUCTURE_NOTIFY | x::EventMask::SUBSTRUCTURE_REDIRECT,
+        event: &event,
+    })?;
+
+    Ok(())
+}
+
+pub fn activate_xcb_window(conn: &X11Connection, window_id: &WindowId) -> Result<(), Error> {
+    let window = xcb_window(conn, window_id)?;
+
+    send_xcb_client_message(
+        conn,
+        window,
+        conn.atoms.net_active_window,
+        [SOURCE_PAGER, x::CURRENT_TIME, 0, 0, 0],
+    )
+}
+
+pub fn move_resize_xcb_window(
+    conn: &X11Connection,
+    window_id: &WindowId,
+    position: &WindowPosition,
+) -> Result<(), Error> {
+    position.check_geometry()?;
+    let window = xcb_window(conn, window_id)?;
+
+    // Static gravity places the client area at x and y, like `ActiveWindow::position` on X11
+    // reports it, instead of the frame. Width and height always are the client's.
+    let flags = STATIC_GRAVITY | 0xf << 8 | SOURCE_PAGER << 12;
+    send_xcb_client_message(
+        conn,
+        window,
+        conn.atoms.net_moveresize_window,
+        [
+            flags,
+            position.x.round() as i32 as u32,
+            position.y.round() as i32 as u32,
+            position.width.round() as u32,
+            position.height.round() as u32,
+        ],
+    )
+}
+
+pub fn set_xcb_window_state(
+    conn: &X11Connection,
+    window_id: &WindowId,
+    state: WindowState,
+    enable: bool,
+) -> Result<(), Error> {
+    let window = xcb_window(conn, window_id)?;
+    let atoms = &conn.atoms;
+
+    let flags = [
+        (
+            WindowState::MAXIMIZED_VERT,
+            atoms.net_wm_state_maximized_vert,
+        ),
+        (
+            WindowState::MAXIMIZED_HORZ,
+            atoms.net_wm_state_maximized_horz,
+        ),
+        (WindowState::FULLSCREEN, atoms.net_wm_state_fullscreen),
+        (WindowState::ABOVE, atoms.net_wm_state_above),
+        (WindowState::STICKY, atoms.net_wm_state_sticky),
+        (
+            WindowState::DEMANDS_ATTENTION,
+            atoms.net_wm_state_demands_attention,
+        ),
+        (WindowState::MODAL, atoms.net_wm_state_modal),
+    ];
+    // Checked before sending anything, so a change is not applied halfway.
+    // The WM never interned a state it does not support.
+    let properties = flags
+        .iter()
+        .filter(|(flag, _)| state.contains(*flag))
+        .map(|(_, atom)| {
+            if *atom == x::ATOM_NONE {
+                Err(Error::EwmhUnsupported)
+            } else {
+                Ok(atom.resource_id())
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // _NET_WM_STATE_HIDDEN is only set by the WM, windows are minimized through ICCCM
+    // and restored by activating them
+    if state.contains(WindowState::MINIMIZED) {
+        if enable {
+            send_xcb_client_message(
+                conn,
+                window,
+                atoms.wm_change_state,
+                [ICONIC_STATE, 0, 0, 0, 0],
+            )?;
+        } else {
+            send_xcb_client_message(
+                conn,
+                window,
+                atoms.net_active_window,
+                [SOURCE_PAGER, x::CURRENT_TIME, 0, 0, 0],
+            )?;
+        }
+    }
+
+    let action = if enable {
+        NET_WM_STATE_ADD
+    } else {
+        NET_WM_STATE_REMOVE
+    };
+    // Each message changes up to two properties, e.g. both maximized directions at once
+    for pair in properties.chunks(2) {
+        send_xcb_client_message(
+            conn,
+            window,
+            atoms.net_wm_state,
+            [
+                action,
+                pair[0],
+                pair.get(1).copied().unwrap_or_default(),
+                SOURCE_PAGER,
+                0,
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn close_xcb_window(conn: &X11Connection, window_id: &WindowId) -> Result<(), Error> {
+    let window = xcb_window(conn, window_id)?;
+
+    send_xcb_client_message(
+        conn,
+        window,
+        conn.atoms.net_close_window,
+        [x::CURRENT_TIME, SOURCE_PAGER, 0, 0, 0],
+    )
+}
@@ -0,0 +1,298 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{ActiveWindow, DesktopEntry, PackageFormat};
+
+// Keys of the [Desktop Entry] group that are needed to match and describe the application
+#[derive(Default)]
+struct DesktopFile {
+    id: String,
+    path: PathBuf,
+    name: Option<String>,
+    generic_name: Option<String>,
+    icon: Option<String>,
+    categories: Vec<String>,
+    startup_wm_class: Option<String>,
+    exec: Option<String>,
+    try_exec: Option<String>,
+}
+
+impl DesktopFile {
+    fn into_entry(self) -> DesktopEntry {
+        DesktopEntry {
+            name: self.name.unwrap_or_else(|| self.id.clone()),
+            id: self.id,
+            path: self.path,
+            generic_name: self.generic_name,
+            icon: self.icon,
+            categories: self.categories,
+        }
+    }
+
+    // Program the entry starts, without its arguments
+    fn executables(&self) -> impl Iterator<Item = &str> {
+        self.try_exec
+            .as_deref()
+            .into_iter()
+            .chain(self.exec.as_deref().and_then(|exec| {
+                exec.split_whitespace()
+                    .find(|arg| *arg != "env" && !arg.contains('='))
+            }))
+            .map(|exec| exec.trim_matches('"'))
+    }
+}
+
+// Data directories in order of preference, see the XDG Base Directory Specification
+fn data_dirs() -> Vec<PathBuf> {
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")));
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_owned());
+
+    data_home
+        .into_iter()
+        .chain(data_dirs.split(':').map(PathBuf::from))
+        .collect()
+}
+
+// Locale keys to try for localized values, most specific first, e.g. `de_AT@euro`,
+// `de_AT`, `de@euro` and `de` for `de_AT.UTF-8@euro`
+fn locale_keys() -> Vec<String> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|locale| !locale.is_empty())
+        .unwrap_or_default();
+
+    let (locale, modifier) = match locale.split_once('@') {
+        Some((locale, modifier)) => (locale, Some(modifier)),
+        None => (locale.as_str(), None),
+    };
+    let locale = locale.split('.').next().unwrap_or_default();
+    let (lang, country) = match locale.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (locale, None),
+    };
+    if lang.is_empty() || lang == "C" || lang == "POSIX" {
+        return Vec::new();
+    }
+
+    let mut keys = Vec::new();
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        keys.push(format!("{}_{}@{}", lang, country, modifier));
+    }
+    if let Some(country) = country {
+        keys.push(format!("{}_{}", lang, country));
+    }
+    if let Some(modifier) = modifier {
+        keys.push(format!("{}@{}", lang, modifier));
+    }
+    keys.push(lang.to_owned());
+    keys
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+// Returns `None` for entries that are not applications or are hidden
+fn parse_desktop_file(id: String, path: PathBuf, locale_keys: &[String]) -> Option<DesktopFile> {
+    let contents = fs::read_to_string(&path).ok()?;
+    let mut file = DesktopFile {
+        id,
+        path,
+        ..Default::default()
+    };
+    // Rank of the locale of the name read so far, lower is better
+    let mut name_rank = usize::MAX;
+    let mut generic_name_rank = usize::MAX;
+    let mut in_entry = false;
+
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=').filter(|_| in_entry) else {
+            continue;
+        };
+        let (key, value) = (key.trim(), unescape(value.trim()));
+        let (key, rank) = match key.split_once('[') {
+            Some((key, locale)) => {
+                let locale = locale.trim_end_matches(']');
+                match locale_keys.iter().position(|known| known == locale) {
+                    Some(rank) => (key, rank),
+                    None => continue,
+                }
+            }
+            None => (key, locale_keys.len()),
+        };
+
+        match key {
+            "Type" if value != "Application" => return None,
+            "Hidden" if value == "true" => return None,
+            "Name" if rank < name_rank => {
+                file.name = Some(value);
+                name_rank = rank;
+            }
+            "GenericName" if rank < generic_name_rank => {
+                file.generic_name = Some(value);
+                generic_name_rank = rank;
+            }
+            "Icon" => file.icon = Some(value),
+            "Categories" => {
+                file.categories = value
+                    .split(';')
+                    .filter(|category| !category.is_empty())
+                    .map(str::to_owned)
+                    .collect()
+            }
+            "StartupWMClass" => file.startup_wm_class = Some(value),
+            "Exec" => file.exec = Some(value),
+            "TryExec" => file.try_exec = Some(value),
+            _ => {}
+        }
+    }
+
+    Some(file)
+}
+
+// Desktop files under `dir`, with ids made of their path relative to `base` joined by dashes
+fn collect_desktop_files(
+    base: &Path,
+    dir: &Path,
+    locale_keys: &[String],
+    seen: &mut HashSet<String>,
+    files: &mut Vec<DesktopFile>,
+) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        // Symlinked directories are not followed, they could point back up the tree
+        if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            collect_desktop_files(base, &path, locale_keys, seen, files);
+            continue;
+        }
+        if path
+            .extension()
+            .is_none_or(|extension| extension != "desktop")
+        {
+            continue;
+        }
+
+        let Ok(relative) = path.strip_prefix(base) else {
+            continue;
+        };
+        let id = relative.to_string_lossy().replace('/', "-");
+        // An id found in a more important data directory shadows the others, even if hidden
+        if seen.insert(id.clone()) {
+            files.extend(parse_desktop_file(id, path, locale_keys));
+        }
+    }
+}
+
+fn desktop_files() -> Vec<DesktopFile> {
+    let locale_keys = locale_keys();
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+
+    for dir in data_dirs() {
+        let applications = dir.join("applications");
+        collect_desktop_files(
+            &applications,
+            &applications,
+            &locale_keys,
+            &mut seen,
+            &mut files,
+        );
+    }
+
+    files
+}
+
+pub fn find_desktop_entry(window: &ActiveWindow) -> Option<DesktopEntry> {
+    let files = desktop_files();
+    let find = |matches: &dyn Fn(&DesktopFile) -> bool| files.iter().position(matches);
+    let id_is = |app_id: &str| {
+        let id = format!("{}.desktop", app_id);
+        move |file: &DesktopFile| file.id.eq_ignore_ascii_case(&id)
+    };
+    let class_names = [&window.wm_class, &window.wm_instance, &window.app_name]
+        .into_iter()
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+
+    let by_package = || {
+        let packaging = window.packaging().ok().flatten()?;
+        match packaging.format {
+            PackageFormat::Flatpak => find(&id_is(&packaging.app_id)),
+            // Snapd names the desktop files it installs `<snap>_<app>.desktop`
+            PackageFormat::Snap => {
+                let prefix = format!("{}_", packaging.app_id);
+                find(&|file: &DesktopFile| file.id.starts_with(&prefix))
+            }
+            _ => None,
+        }
+    };
+    let by_gtk_application_id = || find(&id_is(window.gtk_application_id.as_deref()?));
+    let by_wm_class = || {
+        class_names.iter().find_map(|class| {
+            find(&|file: &DesktopFile| {
+                file.startup_wm_class
+                    .as_ref()
+                    .is_some_and(|wm_class| wm_class.eq_ignore_ascii_case(class))
+            })
+        })
+    };
+    // Many applications name their desktop file after their class, e.g. `firefox.desktop`
+    let by_class_id = || class_names.iter().find_map(|class| find(&id_is(class)));
+    let by_executable = || {
+        let process_path = &window.process_path;
+        let process_name = process_path.file_name()?;
+        find(&|file: &DesktopFile| {
+            file.executables().any(|exec| {
+                let exec = Path::new(exec);
+                if exec.is_absolute() {
+                    exec == process_path
+                } else {
+                    exec.file_name() == Some(process_name)
+                }
+            })
+        })
+    };
+
+    let index = by_package()
+        .or_else(by_gtk_application_id)
+        .or_else(by_wm_class)
+        .or_else(by_class_id)
+        .or_else(by_executable)?;
+
+    files.into_iter().nth(index).map(DesktopFile::into_entry)
+}
@@ -0,0 +1,39 @@
+use xcb::x;
+
+use crate::common::error::Error;
+
+impl From<xcb::ConnError> for Error {
+    fn from(err: xcb::ConnError) -> Self {
+        Error::ConnectionFailed(Box::new(err))
+    }
+}
+
+impl From<xcb::Error> for Error {
+    fn from(err: xcb::Error) -> Self {
+        match err {
+            xcb::Error::Connection(err) => err.into(),
+            xcb::Error::Protocol(err) => err.into(),
+        }
+    }
+}
+
+impl From<xcb::ProtocolError> for Error {
+    fn from(err: xcb::ProtocolError) -> Self {
+        Error::Platform(Box::new(err))
+    }
+}
+
+// Whether a request failed because its window does not exist (anymore)
+pub fn is_bad_window(err: &Error) -> bool {
+    let Error::Platform(err) = err else {
+        return false;
+    };
+
+    matches!(
+        err.downcast_ref::<xcb::ProtocolError>(),
+        Some(xcb::ProtocolError::X(
+            x::Error::Window(_) | x::Error::Drawable(_),
+            _
+        ))
+    )
+}
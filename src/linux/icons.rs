@@ -0,0 +1,128 @@
+use xcb::x;
+
+use super::connection::X11Connection;
+use super::control::xcb_window;
+use super::platform_api::request_xcb_property;
+use crate::{Error, WindowIcon, WindowId};
+
+// _NET_WM_ICON holds any number of icons, each a width and a height followed by
+// width * height pixels as ARGB cardinals
+fn decode_net_wm_icon(mut data: &[u32]) -> Vec<WindowIcon> {
+    let mut icons = Vec::new();
+
+    while let [width, height, rest @ ..] = data {
+        let Some(len) = width
+            .checked_mul(*height)
+            .map(|len| len as usize)
+            .filter(|len| *len <= rest.len())
+        else {
+            break;
+        };
+        let (pixels, next) = rest.split_at(len);
+
+        if len > 0 {
+            icons.push(WindowIcon {
+                width: *width,
+                height: *height,
+                rgba: pixels
+                    .iter()
+                    .flat_map(|argb| {
+                        let [a, r, g, b] = argb.to_be_bytes();
+                        [r, g, b, a]
+                    })
+                    .collect(),
+            });
+        }
+        data = next;
+    }
+
+    icons
+}
+
+pub fn get_xcb_window_icons(
+    conn: &X11Connection,
+    window_id: &WindowId,
+) -> Result<Vec<WindowIcon>, Error> {
+    let window = xcb_window(conn, window_id)?;
+
+    let icon = request_xcb_property(
+        conn,
+        window,
+        conn.atoms.net_wm_icon,
+        x::ATOM_CARDINAL,
+        u32::MAX / 4,
+    );
+    let icon = conn.wait_for_reply(icon)?;
+
+    Ok(decode_net_wm_icon(icon.value::<u32>()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_argb_to_rgba() {
+        let icons = decode_net_wm_icon(&[2, 1, 0x80ff_2010, 0xff00_00ff]);
+
+        assert_eq!(icons.len(), 1);
+        assert_eq!((icons[0].width, icons[0].height), (2, 1));
+        assert_eq!(
+            icons[0].rgba,
+            [0xff, 0x20, 0x10, 0x80, 0x00, 0x00, 0xff, 0xff]
+        );
+    }
+
+    #[test]
+    fn decodes_every_icon() {
+        let mut data = vec![1, 1, 0xffff_0000];
+        data.extend([2, 2, 0, 0, 0, 0]);
+        // Empty icons are skipped, the ones after them still decoded
+        data.extend([0, 5]);
+        data.extend([3, 1, 0, 0, 0]);
+
+        let sizes = decode_net_wm_icon(&data)
+            .iter()
+            .map(|icon| (icon.width, icon.height, icon.rgba.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, [(1, 1, 4), (2, 2, 16), (3, 1, 12)]);
+    }
+
+    #[test]
+    fn stops_at_truncated_data() {
+        // Fewer pixels than the size promises
+        assert_eq!(decode_net_wm_icon(&[1, 1, 0, 2, 2, 0, 0, 0]).len(), 1);
+        // Size cut off
+        assert_eq!(decode_net_wm_icon(&[1, 1, 0, 16]).len(), 1);
+        assert!(decode_net_wm_icon(&[]).is_empty());
+    }
+
+    #[test]
+    fn rejects_sizes_beyond_the_data() {
+        // The product overflows u32
+        assert!(decode_net_wm_icon(&[u32::MAX, 2, 0, 0]).is_empty());
+        assert!(decode_net_wm_icon(&[0x1_0000, 0x1_0000, 0]).is_empty());
+        // Fits u32, but not the remaining data
+        assert!(decode_net_wm_icon(&[u32::MAX, 1, 0]).is_empty());
+    }
+
+    #[test]
+    fn picks_the_decoded_icon_closest_to_a_size() {
+        let mut data = Vec::new();
+        for (width, height) in [(16, 16), (48, 24), (32, 32)] {
+            data.extend([width, height]);
+            data.resize(data.len() + (width * height) as usize, 0);
+        }
+        let icons = decode_net_wm_icon(&data);
+
+        let closest = |size| {
+            let icon = WindowIcon::closest(&icons, size).unwrap();
+            (icon.width, icon.height)
+        };
+        assert_eq!(closest(16), (16, 16));
+        assert_eq!(closest(34), (32, 32));
+        // The larger side counts, a tie goes to the larger icon
+        assert_eq!(closest(40), (48, 24));
+        assert_eq!(closest(1), (16, 16));
+    }
+}
@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+use xcb::{screensaver, x};
+
+use super::connection::X11Connection;
+use crate::Error;
+
+// The X server counts the time since the last input event of any device
+pub fn get_xcb_idle_time(conn: &X11Connection) -> Result<Duration, Error> {
+    if !conn.has_screensaver {
+        return Err(Error::Unsupported);
+    }
+
+    let info = conn.wait_for_reply(conn.send_request(&screensaver::QueryInfo {
+        drawable: x::Drawable::Window(conn.root),
+    }))?;
+
+    Ok(Duration::from_millis(info.ms_since_user_input().into()))
+}
@@ -0,0 +1,403 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::fd::RawFd;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::process;
+use std::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use dbus::blocking::Connection;
+use dbus::channel::{BusType, Channel, MatchingReceiver};
+use dbus::message::MatchRule;
+use serde_json::Value;
+
+use crate::Error;
+
+const DBUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Helpers shared by every script, `{{dbus_addr}}` and `{{kde5}}` are filled in by `KWinScript::load`.
+// KWin 5 and 6 expose windows through differently named workspace properties.
+const SCRIPT_HEADER: &str = r#"
+const kde5 = {{kde5}};
+
+function output_result(message) {
+    callDBus("{{dbus_addr}}", "/", "", "result", JSON.stringify(message));
+}
+
+function output_error(message) {
+    callDBus("{{dbus_addr}}", "/", "", "error", message.toString());
+}
+
+function workspace_windowList() {
+    return kde5 ? workspace.clientList() : workspace.windowList();
+}
+
+function workspace_activeWindow() {
+    return kde5 ? workspace.activeClient : workspace.activeWindow;
+}
+
+function workspace_windowActivated() {
+    return kde5 ? workspace.clientActivated : workspace.windowActivated;
+}
+
+function workspace_activateWindow(w) {
+    if (kde5) {
+        workspace.activeClient = w;
+    } else {
+        workspace.activeWindow = w;
+    }
+}
+
+function window_byId(id) {
+    return workspace_windowList().find((w) => w.internalId.toString() === id) || null;
+}
+
+function window_setGeometry(w, x, y, width, height) {
+    const geometry = { x: x, y: y, width: width, height: height };
+    if (kde5) {
+        w.geometry = geometry;
+    } else {
+        w.frameGeometry = geometry;
+    }
+}
+
+function rect_info(rect) {
+    return { x: rect.x, y: rect.y, width: rect.width, height: rect.height };
+}
+
+function area_info(name, area, scale) {
+    return { name: name, x: area.x, y: area.y, width: area.width, height: area.height, scale: scale };
+}
+
+function output_info(output) {
+    return area_info(output.name, output.geometry, output.devicePixelRatio);
+}
+
+// KWin 5 only exposes the index and area of screens, not their names or scale
+function screen_info(screen) {
+    return area_info("", workspace.clientArea(KWin.ScreenArea, screen, workspace.currentDesktop), 1);
+}
+
+function workspace_outputList() {
+    if (kde5) {
+        return Array.from({ length: workspace.numScreens }, (_, screen) => screen_info(screen));
+    }
+    return workspace.screens.map(output_info);
+}
+
+function window_output(w) {
+    if (kde5) {
+        return screen_info(w.screen);
+    }
+    return w.output ? output_info(w.output) : null;
+}
+
+// KWin has no maximized property, a maximized window fills the area it may be maximized to
+function window_state(w) {
+    const area = workspace.clientArea(KWin.MaximizeArea, w);
+    return {
+        minimized: w.minimized,
+        maximized_vert: w.y === area.y && w.height === area.height,
+        maximized_horz: w.x === area.x && w.width === area.width,
+        fullscreen: w.fullScreen,
+        above: w.keepAbove,
+        sticky: w.onAllDesktops,
+        demands_attention: w.demandsAttention,
+        modal: w.modal,
+    };
+}
+
+// `state` holds the flags to change under the names used by `window_state`, modal is read-only
+function window_setState(w, state) {
+    if ("minimized" in state) {
+        w.minimized = state.minimized;
+    }
+    if ("maximized_vert" in state || "maximized_horz" in state) {
+        const current = window_state(w);
+        w.setMaximize(
+            "maximized_vert" in state ? state.maximized_vert : current.maximized_vert,
+            "maximized_horz" in state ? state.maximized_horz : current.maximized_horz
+        );
+    }
+    if ("fullscreen" in state) {
+        w.fullScreen = state.fullscreen;
+    }
+    if ("above" in state) {
+        w.keepAbove = state.above;
+    }
+    if ("sticky" in state) {
+        w.onAllDesktops = state.sticky;
+    }
+    if ("demands_attention" in state) {
+        w.demandsAttention = state.demands_attention;
+    }
+}
+
+function window_type(w) {
+    const types = [
+        ["dialog", w.dialog],
+        ["dock", w.dock],
+        ["desktop", w.desktopWindow],
+        ["toolbar", w.toolbar],
+        ["menu", w.menu],
+        ["utility", w.utility],
+        ["splash", w.splash],
+        ["dropdown_menu", w.dropdownMenu],
+        ["popup_menu", w.popupMenu],
+        ["tooltip", w.tooltip],
+        ["notification", w.notification],
+        ["combo", w.comboBox],
+        ["dnd", w.dndIcon],
+    ];
+    const type = types.find(([_, is_type]) => is_type);
+    return type ? type[0] : "normal";
+}
+
+// KWin 5 numbers desktops from 1, KWin 6 has desktop objects
+function workspace_desktopList() {
+    if (kde5) {
+        return Array.from({ length: workspace.desktops }, (_, index) => ({
+            index: index,
+            name: workspace.desktopName(index + 1),
+            current: index + 1 === workspace.currentDesktop,
+        }));
+    }
+    return workspace.desktops.map((d, index) => ({
+        index: index,
+        name: d.name,
+        current: d === workspace.currentDesktop,
+    }));
+}
+
+// Null for windows on all desktops, a window on several KWin 6 desktops reports none
+function window_desktop(w) {
+    const index = kde5
+        ? w.desktop - 1
+        : w.desktops.length === 1 ? workspace.desktops.indexOf(w.desktops[0]) : -1;
+    return index >= 0 ? workspace_desktopList()[index] : null;
+}
+
+function window_info(w) {
+    return {
+        id: w.internalId.toString(),
+        title: w.caption,
+        class_name: w.resourceClass.toString(),
+        instance_name: w.resourceName.toString(),
+        role: w.windowRole.toString(),
+        pid: w.pid,
+        x: w.x,
+        y: w.y,
+        width: w.width,
+        height: w.height,
+        // Frame geometry above, the area without decorations here
+        client: w.clientGeometry ? rect_info(w.clientGeometry) : null,
+        active: w === workspace_activeWindow(),
+        output: window_output(w),
+        state: window_state(w),
+        type: window_type(w),
+        transient_for: w.transientFor ? w.transientFor.internalId.toString() : null,
+        desktop: window_desktop(w),
+    };
+}
+
+try {
+"#;
+
+const SCRIPT_FOOTER: &str = r#"
+} catch (e) {
+    output_error(e);
+}
+"#;
+
+fn backend_error(err: impl Into<crate::common::error::BoxedError>) -> Error {
+    Error::BackendUnavailable(err.into())
+}
+
+fn unique_script_name() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_nanos())
+        .unwrap_or_default();
+
+    format!("active-win-pos-rs-{}-{}", process::id(), nanos)
+}
+
+// Only the user may read the script, it names our D-Bus connection. `create_new` refuses to
+// follow a file or link someone else planted under the same name.
+fn write_script_file(name: &str, script: &str) -> Result<PathBuf, Error> {
+    let dir = env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(env::temp_dir);
+    let path = dir.join(format!("{}.js", name));
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)
+        .map_err(backend_error)?;
+    if let Err(err) = file.write_all(script.as_bytes()) {
+        let _ = fs::remove_file(&path);
+        return Err(backend_error(err));
+    }
+
+    Ok(path)
+}
+
+fn parse_payload(payload: &str) -> Result<Value, Error> {
+    // KWin may hand the JSON string over with its quotes escaped
+    serde_json::from_str::<Value>(payload)
+        .ok()
+        .and_then(|value| match value {
+            Value::String(inner) => serde_json::from_str(&inner).ok(),
+            value => Some(value),
+        })
+        .ok_or_else(|| backend_error(format!("unexpected KWin script output: {}", payload)))
+}
+
+enum ScriptMessage {
+    Result(String),
+    Error(String),
+}
+
+// KWin script loaded into the compositor, unloaded on drop.
+// KWin has no API to query windows directly on Wayland, scripts report back over D-Bus.
+pub struct KWinScript {
+    conn: Connection,
+    messages: mpsc::Receiver<ScriptMessage>,
+    name: String,
+    path: PathBuf,
+}
+
+impl KWinScript {
+    pub fn load(body: &str) -> Result<Self, Error> {
+        let mut channel = Channel::get_private(BusType::Session).map_err(backend_error)?;
+        // Exposes the connection's file descriptor, see `fd`
+        channel.set_watch_enabled(true);
+        let conn = Connection::from(channel);
+        let kde5 = env::var("KDE_SESSION_VERSION").is_ok_and(|version| version == "5");
+
+        let (tx, messages) = mpsc::channel();
+        conn.start_receive(
+            MatchRule::new_method_call(),
+            Box::new(move |message, _| {
+                if let (Some(member), Some(arg)) = (message.member(), message.get1::<String>()) {
+                    let _ = match &*member {
+                        "result" => tx.send(ScriptMessage::Result(arg)),
+                        "error" => tx.send(ScriptMessage::Error(arg)),
+                        _ => Ok(()),
+                    };
+                }
+                true
+            }),
+        );
+
+        let script = SCRIPT_HEADER
+            .replace("{{kde5}}", if kde5 { "true" } else { "false" })
+            .replace("{{dbus_addr}}", &conn.unique_name())
+            + body
+            + SCRIPT_FOOTER;
+
+        let name = unique_script_name();
+        let path = write_script_file(&name, &script)?;
+
+        // From here on the file is removed on drop, also when running the script fails
+        let script = Self {
+            conn,
+            messages,
+            name,
+            path,
+        };
+        script.run(kde5)?;
+
+        Ok(script)
+    }
+
+    fn run(&self, kde5: bool) -> Result<(), Error> {
+        let scripting = self
+            .conn
+            .with_proxy("org.kde.KWin", "/Scripting", DBUS_TIMEOUT);
+
+        let (script_id,): (i32,) = scripting
+            .method_call(
+                "org.kde.kwin.Scripting",
+                "loadScript",
+                (self.path.to_string_lossy().as_ref(), self.name.as_str()),
+            )
+            .map_err(backend_error)?;
+        if script_id < 0 {
+            return Err(backend_error("KWin refused to load the script"));
+        }
+
+        let script_object = if kde5 {
+            format!("/{}", script_id)
+        } else {
+            format!("/Scripting/Script{}", script_id)
+        };
+        let script = self
+            .conn
+            .with_proxy("org.kde.KWin", script_object, DBUS_TIMEOUT);
+
+        script
+            .method_call("org.kde.kwin.Script", "run", ())
+            .map_err(backend_error)
+    }
+
+    pub fn fd(&self) -> RawFd {
+        self.conn.channel().watch().fd
+    }
+
+    // Returns the next value the script passed to `output_result` without blocking
+    pub fn try_next(&self) -> Result<Option<Value>, Error> {
+        loop {
+            match self.messages.try_recv() {
+                Ok(ScriptMessage::Result(payload)) => return parse_payload(&payload).map(Some),
+                Ok(ScriptMessage::Error(message)) => {
+                    return Err(backend_error(format!("KWin script error: {}", message)))
+                }
+                Err(_) => {
+                    if !self.conn.process(Duration::ZERO).map_err(backend_error)? {
+                        return Ok(None);
+                    }
+                }
+            }
+        }
+    }
+
+    // Waits for the first value the script passes to `output_result`
+    pub fn next(&self, timeout: Duration) -> Result<Value, Error> {
+        let start = Instant::now();
+
+        loop {
+            if let Some(value) = self.try_next()? {
+                return Ok(value);
+            }
+            if start.elapsed() > timeout {
+                return Err(backend_error("timed out waiting for KWin"));
+            }
+
+            self.conn
+                .process(Duration::from_millis(100))
+                .map_err(backend_error)?;
+        }
+    }
+}
+
+impl Drop for KWinScript {
+    fn drop(&mut self) {
+        let scripting = self
+            .conn
+            .with_proxy("org.kde.KWin", "/Scripting", DBUS_TIMEOUT);
+        let _: Result<(), _> =
+            scripting.method_call("org.kde.kwin.Scripting", "unloadScript", (&self.name,));
+
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// Runs a script that reports a single result
+pub fn run_script(body: &str) -> Result<Value, Error> {
+    KWinScript::load(body)?.next(DBUS_TIMEOUT)
+}
@@ -1,9 +1,33 @@
+mod capture;
+mod connection;
+mod control;
+mod desktop_entry;
+mod error;
+mod icons;
+mod idle;
+mod kwin;
+mod monitors;
+mod packaging;
 mod platform_api;
+mod process;
+mod watch;
 mod wayland;
+mod workspaces;
+
+use std::sync::Arc;
 
 use crate::common::platform_api::PlatformApi;
-use platform_api::LinuxPlatformApi;
+use platform_api::X11PlatformApi;
+use wayland::KWinPlatformApi;
+
+pub use desktop_entry::find_desktop_entry;
+pub use packaging::get_process_packaging;
+pub use process::read_process_info;
 
-pub fn init_platform_api() -> impl PlatformApi {
-    LinuxPlatformApi {}
+// In the order `Backend::Auto` tries them
+pub fn builtin_backends() -> Vec<Arc<dyn PlatformApi>> {
+    vec![
+        Arc::new(KWinPlatformApi),
+        Arc::new(X11PlatformApi::default()),
+    ]
 }
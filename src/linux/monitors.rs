@@ -0,0 +1,159 @@
+use xcb::{randr, x, Xid};
+
+use super::connection::X11Connection;
+use super::platform_api::request_xcb_property;
+use crate::{CoordinateSpace, Error, Monitor, WindowPosition};
+
+// Scale desktops configure for X11 applications through `Xft.dpi` in the resource database
+fn xft_scale_factor(resources: &[u8]) -> Option<f64> {
+    String::from_utf8_lossy(resources)
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            match name.trim() {
+                "Xft.dpi" => value.trim().parse::<f64>().ok(),
+                _ => None,
+            }
+        })
+        .filter(|dpi| *dpi > 0.0)
+        .map(|dpi| dpi / 96.0)
+}
+
+fn refresh_rate(mode: &randr::ModeInfo) -> f64 {
+    let mut vtotal = f64::from(mode.vtotal);
+    if mode.mode_flags.contains(randr::ModeFlag::DOUBLE_SCAN) {
+        vtotal *= 2.0;
+    }
+    if mode.mode_flags.contains(randr::ModeFlag::INTERLACE) {
+        vtotal /= 2.0;
+    }
+
+    let dots = f64::from(mode.htotal) * vtotal;
+    if dots == 0.0 {
+        return 0.0;
+    }
+
+    f64::from(mode.dot_clock) / dots
+}
+
+// Monitors to place windows on, empty if RandR is not available.
+// Cached on the connection, so placing a window does not cost the RandR round trips.
+pub fn get_xcb_monitors(conn: &X11Connection) -> Vec<Monitor> {
+    conn.cached_monitors()
+        .or_else(|| list_xcb_monitors(conn).ok())
+        .unwrap_or_default()
+}
+
+// Lists connected outputs that drive a CRTC, requesting all of their info up front
+pub fn list_xcb_monitors(conn: &X11Connection) -> Result<Vec<Monitor>, Error> {
+    let monitors = query_xcb_monitors(conn)?;
+    conn.cache_monitors(&monitors);
+
+    Ok(monitors)
+}
+
+fn query_xcb_monitors(conn: &X11Connection) -> Result<Vec<Monitor>, Error> {
+    if !conn.has_randr {
+        return Err(Error::Unsupported);
+    }
+
+    let resources = conn.send_request(&randr::GetScreenResourcesCurrent { window: conn.root });
+    let primary = conn.send_request(&randr::GetOutputPrimary { window: conn.root });
+    let xresources = request_xcb_property(
+        conn,
+        conn.root,
+        x::ATOM_RESOURCE_MANAGER,
+        x::ATOM_STRING,
+        u32::MAX,
+    );
+    // Every reply is taken before bailing out on an error, see `XcbWindowCookies::wait`
+    let resources = conn.wait_for_reply(resources);
+    let primary = conn.wait_for_reply(primary);
+    let xresources = conn.wait_for_reply(xresources);
+    let resources = resources?;
+    let primary = primary?.output();
+    let scale_factor = xresources
+        .ok()
+        .and_then(|xresources| xft_scale_factor(xresources.value()))
+        .unwrap_or(1.0);
+
+    let config_timestamp = resources.config_timestamp();
+    let outputs = resources
+        .outputs()
+        .iter()
+        .map(|output| {
+            let cookie = conn.send_request(&randr::GetOutputInfo {
+                output: *output,
+                config_timestamp,
+            });
+            (*output, cookie)
+        })
+        .collect::<Vec<_>>();
+
+    let outputs = outputs
+        .into_iter()
+        .map(|(output, cookie)| (output, conn.wait_for_reply(cookie)))
+        .collect::<Vec<_>>();
+
+    let mut outputs_with_crtc = Vec::with_capacity(outputs.len());
+    for (output, info) in outputs {
+        let info = match info {
+            Ok(info) => info,
+            Err(err) => {
+                // Not waiting on the CRTCs requested so far would leave their replies queued
+                for (_, _, crtc) in outputs_with_crtc {
+                    let _ = conn.wait_for_reply(crtc);
+                }
+                return Err(err.into());
+            }
+        };
+        if info.connection() != randr::Connection::Connected || info.crtc().is_none() {
+            continue;
+        }
+
+        let crtc = conn.send_request(&randr::GetCrtcInfo {
+            crtc: info.crtc(),
+            config_timestamp,
+        });
+        outputs_with_crtc.push((output, info, crtc));
+    }
+
+    let outputs_with_crtc = outputs_with_crtc
+        .into_iter()
+        .map(|(output, info, crtc)| (output, info, conn.wait_for_reply(crtc)))
+        .collect::<Vec<_>>();
+
+    let mut monitors = Vec::with_capacity(outputs_with_crtc.len());
+    for (output, info, crtc) in outputs_with_crtc {
+        let crtc = crtc?;
+        // Disabled CRTCs have no mode
+        if crtc.mode().is_none() {
+            continue;
+        }
+
+        let refresh_rate = resources
+            .modes()
+            .iter()
+            .find(|mode| mode.id == crtc.mode().resource_id())
+            .map(refresh_rate)
+            .unwrap_or_default();
+
+        monitors.push(Monitor {
+            name: String::from_utf8_lossy(info.name()).into_owned(),
+            position: WindowPosition::new(
+                f64::from(crtc.x()),
+                f64::from(crtc.y()),
+                f64::from(crtc.width()),
+                f64::from(crtc.height()),
+            ),
+            is_primary: output == primary,
+            refresh_rate,
+            width_mm: info.mm_width(),
+            height_mm: info.mm_height(),
+            scale_factor,
+            coordinate_space: CoordinateSpace::Physical,
+        });
+    }
+
+    Ok(monitors)
+}
@@ -0,0 +1,159 @@
+use std::fs;
+use std::path::Path;
+
+use crate::{PackageFormat, Packaging};
+
+// Value of `[Application] name=` in the .flatpak-info file Flatpak puts at the sandbox root
+fn flatpak_app_id(flatpak_info: &str) -> Option<String> {
+    let mut in_application = false;
+
+    for line in flatpak_info.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_application = line == "[Application]";
+        } else if in_application {
+            if let Some(name) = line.strip_prefix("name=") {
+                return Some(name.to_owned());
+            }
+        }
+    }
+
+    None
+}
+
+// Snap name from a cgroup like `0::/user.slice/.../snap.firefox.firefox-1234.scope`
+fn snap_name_from_cgroup(cgroup: &str) -> Option<String> {
+    cgroup
+        .lines()
+        .flat_map(|line| line.split('/'))
+        .find_map(|unit| unit.strip_prefix("snap."))
+        .and_then(|unit| unit.split('.').next())
+        .filter(|name| !name.is_empty())
+        .map(str::to_owned)
+}
+
+fn environ_var(environ: &[u8], name: &str) -> Option<String> {
+    environ
+        .split(|byte| *byte == 0)
+        .map(String::from_utf8_lossy)
+        .find_map(|var| {
+            var.strip_prefix(name)
+                .and_then(|var| var.strip_prefix('='))
+                .map(str::to_owned)
+        })
+}
+
+// AppImage file name without its extension, for a process running from the mounted image.
+// Its child processes inherit APPIMAGE, but their executable lies outside APPDIR.
+fn appimage_app_id(environ: &[u8], exe: &Path) -> Option<String> {
+    let app_dir = environ_var(environ, "APPDIR")?;
+    if !exe.starts_with(app_dir) {
+        return None;
+    }
+
+    let path = environ_var(environ, "APPIMAGE")?;
+    let path = Path::new(&path);
+    Some(
+        path.file_stem()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+// Looks for the traces each format leaves in /proc, the environment is only readable for
+// processes of the same user
+pub fn get_process_packaging(pid: u64) -> Option<Packaging> {
+    let proc_dir = Path::new("/proc").join(pid.to_string());
+    let packaging = |format, app_id| Packaging { format, app_id };
+
+    if let Some(app_id) = fs::read_to_string(proc_dir.join("root/.flatpak-info"))
+        .ok()
+        .and_then(|info| flatpak_app_id(&info))
+    {
+        return Some(packaging(PackageFormat::Flatpak, app_id));
+    }
+
+    // The environment is inherited by child processes, e.g. a terminal started from a snap has
+    // SNAP_NAME set while it runs in a scope of its own. The cgroup tells them apart.
+    let environ = fs::read(proc_dir.join("environ")).unwrap_or_default();
+    let snap_name = match fs::read_to_string(proc_dir.join("cgroup")) {
+        Ok(cgroup) => snap_name_from_cgroup(&cgroup),
+        Err(_) => environ_var(&environ, "SNAP_NAME"),
+    };
+    if let Some(name) = snap_name {
+        return Some(packaging(PackageFormat::Snap, name));
+    }
+
+    let exe = fs::read_link(proc_dir.join("exe")).ok()?;
+    appimage_app_id(&environ, &exe).map(|app_id| packaging(PackageFormat::AppImage, app_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatpak_app_id_comes_from_the_application_group() {
+        let info = "[Application]\n\
+                    name=org.mozilla.firefox\n\
+                    runtime=runtime/org.freedesktop.Platform/x86_64/23.08\n\
+                    \n\
+                    [Instance]\n\
+                    instance-id=1234\n";
+        assert_eq!(flatpak_app_id(info).as_deref(), Some("org.mozilla.firefox"));
+
+        // `name=` of other groups is not the application id
+        let info = "[Runtime]\nname=org.freedesktop.Platform\n[Application]\nruntime=x\n";
+        assert_eq!(flatpak_app_id(info), None);
+    }
+
+    #[test]
+    fn snap_name_comes_from_the_scope() {
+        let cgroup = "0::/user.slice/user-1000.slice/user@1000.service/app.slice/\
+                      snap.firefox.firefox-6c0d4d4b-5ad4-4e61-9bbf-e4f6d0b4f0c1.scope\n";
+        assert_eq!(snap_name_from_cgroup(cgroup).as_deref(), Some("firefox"));
+
+        let cgroup = "12:pids:/user.slice/user-1000.slice/session-2.scope\n\
+                      0::/user.slice/user-1000.slice/user@1000.service/app.slice/\
+                      snap.code.code-1234.scope\n";
+        assert_eq!(snap_name_from_cgroup(cgroup).as_deref(), Some("code"));
+
+        let cgroup = "0::/user.slice/user-1000.slice/user@1000.service/app.slice/\
+                      app-org.gnome.Terminal.slice/vte-spawn-1234.scope\n";
+        assert_eq!(snap_name_from_cgroup(cgroup), None);
+    }
+
+    #[test]
+    fn environ_var_matches_whole_names() {
+        let environ = b"HOME=/home/user\0APPIMAGE_X=/tmp/x\0APPIMAGE=/opt/Krita.AppImage\0";
+        assert_eq!(
+            environ_var(environ, "APPIMAGE").as_deref(),
+            Some("/opt/Krita.AppImage")
+        );
+        assert_eq!(environ_var(environ, "HOME").as_deref(), Some("/home/user"));
+        assert_eq!(environ_var(environ, "SNAP_NAME"), None);
+
+        // Only a longer name that shares the prefix is set
+        assert_eq!(environ_var(b"APPIMAGE_X=/tmp/x\0", "APPIMAGE"), None);
+        assert_eq!(environ_var(b"", "APPIMAGE"), None);
+    }
+
+    #[test]
+    fn appimage_only_for_processes_from_the_image() {
+        let environ = b"APPDIR=/tmp/.mount_KritaX1\0APPIMAGE=/opt/Krita.AppImage\0";
+
+        assert_eq!(
+            appimage_app_id(environ, Path::new("/tmp/.mount_KritaX1/usr/bin/krita")).as_deref(),
+            Some("Krita")
+        );
+        // A terminal started from the AppImage inherits its environment
+        assert_eq!(appimage_app_id(environ, Path::new("/usr/bin/xterm")), None);
+        assert_eq!(
+            appimage_app_id(
+                b"APPIMAGE=/opt/Krita.AppImage\0",
+                Path::new("/usr/bin/krita")
+            ),
+            None
+        );
+    }
+}
@@ -1,205 +1,609 @@
 use std::env;
 use std::fs::read_link;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
 
 use xcb::{x, Xid};
 
-use super::wayland::get_active_window_wayland;
-use crate::{common::platform_api::PlatformApi, ActiveWindow, WindowPosition};
+use super::capture::capture_xcb_window;
+use super::connection::X11Connection;
+use super::control::{
+    activate_xcb_window, close_xcb_window, move_resize_xcb_window, set_xcb_window_state,
+};
+use super::error::is_bad_window;
+use super::icons::get_xcb_window_icons;
+use super::idle::get_xcb_idle_time;
+use super::monitors::{get_xcb_monitors, list_xcb_monitors};
+use super::watch::{FdWindowEvents, XcbWindowEvents};
+use super::workspaces::{list_xcb_workspaces, XcbWorkspaceCookies};
+use crate::common::{monitor::find_monitor, platform_api::PlatformApi, watcher::WindowEvents};
+use crate::{
+    ActiveWindow, Backend, CaptureArea, CoordinateSpace, Error, Monitor, Screenshot, WindowIcon,
+    WindowId, WindowPosition, WindowState, WindowType, Workspace,
+};
 
-fn get_xcb_window_pid(conn: &xcb::Connection, window: x::Window) -> xcb::Result<u32> {
-    let window_pid = conn.send_request(&x::InternAtom {
-        only_if_exists: true,
-        name: b"_NET_WM_PID",
-    });
-    let window_pid = conn.wait_for_reply(window_pid)?.atom();
-
-    let window_pid = conn.send_request(&x::GetProperty {
+pub fn request_xcb_property(
+    conn: &X11Connection,
+    window: x::Window,
+    property: x::Atom,
+    r#type: x::Atom,
+    long_length: u32,
+) -> x::GetPropertyCookie {
+    conn.send_request(&x::GetProperty {
         delete: false,
         window,
-        property: window_pid,
-        r#type: x::ATOM_ANY,
+        property,
+        r#type,
         long_offset: 0,
-        long_length: 1,
-    });
-    let window_pid = conn.wait_for_reply(window_pid)?;
+        long_length,
+    })
+}
 
-    Ok(window_pid.value::<u32>().first().unwrap_or(&0).to_owned())
+// Every property needed to describe a window is requested up front,
+// so the replies arrive in a single round trip
+pub struct XcbWindowCookies {
+    pub window: x::Window,
+    pid: x::GetPropertyCookie,
+    geometry: x::GetGeometryCookie,
+    translated_position: x::TranslateCoordinatesCookie,
+    ewmh_title: x::GetPropertyCookie,
+    title: x::GetPropertyCookie,
+    class: x::GetPropertyCookie,
+    gtk_application_id: x::GetPropertyCookie,
+    window_role: x::GetPropertyCookie,
+    state: x::GetPropertyCookie,
+    window_type: x::GetPropertyCookie,
+    transient_for: x::GetPropertyCookie,
+    desktop: x::GetPropertyCookie,
+    frame_extents: x::GetPropertyCookie,
 }
 
-//Uses _NET_WM_NAME to get window title in UTF-8 encoding
-fn get_ewmh_window_title(conn: &xcb::Connection, window: x::Window) -> xcb::Result<String> {
-    let wm_name_atom = conn.send_request(&x::InternAtom {
-        only_if_exists: true,
-        name: "_NET_WM_NAME".as_bytes(),
-    });
-    let wm_name_atom = conn.wait_for_reply(wm_name_atom)?.atom();
+impl XcbWindowCookies {
+    pub fn send(conn: &X11Connection, window: x::Window) -> Self {
+        Self {
+            window,
+            pid: request_xcb_property(conn, window, conn.atoms.net_wm_pid, x::ATOM_ANY, 1),
+            geometry: conn.send_request(&x::GetGeometry {
+                drawable: x::Drawable::Window(window),
+            }),
+            translated_position: conn.send_request(&x::TranslateCoordinates {
+                dst_window: conn.root,
+                src_window: window,
+                src_x: 0,
+                src_y: 0,
+            }),
+            ewmh_title: request_xcb_property(
+                conn,
+                window,
+                conn.atoms.net_wm_name,
+                x::ATOM_ANY,
+                1024,
+            ),
+            title: request_xcb_property(conn, window, x::ATOM_WM_NAME, x::ATOM_ANY, 1024),
+            class: request_xcb_property(conn, window, x::ATOM_WM_CLASS, x::ATOM_ANY, 1024),
+            gtk_application_id: request_xcb_property(
+                conn,
+                window,
+                conn.atoms.gtk_application_id,
+                x::ATOM_ANY,
+                1024,
+            ),
+            window_role: request_xcb_property(
+                conn,
+                window,
+                conn.atoms.wm_window_role,
+                x::ATOM_ANY,
+                1024,
+            ),
+            state: request_xcb_property(conn, window, conn.atoms.net_wm_state, x::ATOM_ATOM, 64),
+            window_type: request_xcb_property(
+                conn,
+                window,
+                conn.atoms.net_wm_window_type,
+                x::ATOM_ATOM,
+                64,
+            ),
+            transient_for: request_xcb_property(
+                conn,
+                window,
+                x::ATOM_WM_TRANSIENT_FOR,
+                x::ATOM_WINDOW,
+                1,
+            ),
+            desktop: request_xcb_property(
+                conn,
+                window,
+                conn.atoms.net_wm_desktop,
+                x::ATOM_CARDINAL,
+                1,
+            ),
+            frame_extents: request_xcb_property(
+                conn,
+                window,
+                conn.atoms.net_frame_extents,
+                x::ATOM_CARDINAL,
+                4,
+            ),
+        }
+    }
 
-    let window_title = conn.send_request(&x::GetProperty {
-        delete: false,
-        window,
-        property: wm_name_atom,
-        r#type: x::ATOM_ANY,
-        long_offset: 0,
-        long_length: 1024,
-    });
-    let window_title = conn.wait_for_reply(window_title)?;
+    // The workspace only has its index, see `place_xcb_window`
+    pub fn wait(self, conn: &X11Connection) -> Result<ActiveWindow, Error> {
+        // Every reply is taken before bailing out on an error,
+        // unclaimed replies would pile up on the cached connection
+        let pid = conn.wait_for_reply(self.pid);
+        let geometry = conn.wait_for_reply(self.geometry);
+        let translated_position = conn.wait_for_reply(self.translated_position);
+        let ewmh_title = conn.wait_for_reply(self.ewmh_title);
+        let title = conn.wait_for_reply(self.title);
+        let class = conn.wait_for_reply(self.class);
+        let gtk_application_id = conn.wait_for_reply(self.gtk_application_id);
+        let window_role = conn.wait_for_reply(self.window_role);
+        let state = conn.wait_for_reply(self.state);
+        let window_type = conn.wait_for_reply(self.window_type);
+        let transient_for = conn.wait_for_reply(self.transient_for);
+        let desktop = conn.wait_for_reply(self.desktop);
+        let frame_extents = conn.wait_for_reply(self.frame_extents);
 
-    let window_title = String::from_utf8_lossy(window_title.value());
-    Ok(window_title.into_owned())
-}
+        let window_pid = get_xcb_window_pid(&pid?);
+        let position = get_xcb_translated_position(&geometry?, &translated_position?);
+        let title = get_xcb_window_title(ewmh_title, title)?;
+        let window_class = get_xcb_window_class(&class?);
+        let gtk_application_id = get_xcb_string_property(&gtk_application_id?);
+        let window_role = get_xcb_string_property(&window_role?);
+        let state = get_xcb_window_state(conn, &state?);
+        let transient_for = get_xcb_transient_for(&transient_for?);
+        let window_type =
+            get_xcb_window_type(conn, &window_type?).unwrap_or(if transient_for.is_some() {
+                WindowType::Dialog
+            } else {
+                WindowType::Normal
+            });
+        let desktop = desktop?;
+        let frame_rect = get_xcb_frame_rect(conn, self.window, &position, &frame_extents?);
 
-fn get_xcb_window_title(conn: &xcb::Connection, window: x::Window) -> xcb::Result<String> {
-    let ewmh_window_title = get_ewmh_window_title(conn, window);
+        let mut process_name = window_class
+            .split('\u{0}')
+            .filter(|str| !str.is_empty())
+            .collect::<Vec<&str>>();
+        let process_name = process_name.pop().unwrap_or("").to_owned();
+
+        // WM_CLASS holds the instance and the class name, each terminated by a NUL
+        let mut class_names = window_class.split('\u{0}');
+        let wm_instance = class_names.next().unwrap_or_default().to_owned();
+        let wm_class = class_names.next().unwrap_or_default().to_owned();
+
+        let process_path = read_link(format!("/proc/{}/exe", window_pid));
 
-    if ewmh_window_title.is_ok() {
-        return ewmh_window_title;
+        Ok(ActiveWindow {
+            process_id: window_pid.into(),
+            window_id: WindowId::X11(self.window.resource_id()),
+            app_name: process_name,
+            wm_instance,
+            wm_class,
+            gtk_application_id,
+            window_role,
+            client_rect: position.clone(),
+            frame_rect,
+            position,
+            title,
+            process_path: process_path.unwrap_or_default(),
+            is_active: false,
+            backend: Backend::X11,
+            monitor: None,
+            coordinate_space: CoordinateSpace::Physical,
+            state,
+            window_type,
+            transient_for: transient_for.map(|window| WindowId::X11(window.resource_id())),
+            workspace: get_xcb_window_desktop(&desktop).map(|index| Workspace {
+                index,
+                ..Default::default()
+            }),
+        })
     }
+}
 
-    let window_title = conn.send_request(&x::GetProperty {
-        delete: false,
-        window,
-        property: x::ATOM_WM_NAME,
-        r#type: x::ATOM_ANY,
-        long_offset: 0,
-        long_length: 1024,
-    });
-    let window_title = conn.wait_for_reply(window_title)?;
-    let window_title = String::from_utf8_lossy(window_title.value());
+// Text of a property any client may set, empty unless it is in the 8-bit format text uses.
+// Reading it as bytes otherwise fails xcb's format assertion.
+fn get_xcb_text(reply: &x::GetPropertyReply) -> &[u8] {
+    if reply.format() == 8 {
+        reply.value::<u8>()
+    } else {
+        &[]
+    }
+}
+
+fn get_xcb_window_pid(reply: &x::GetPropertyReply) -> u32 {
+    reply.value::<u32>().first().unwrap_or(&0).to_owned()
+}
+
+//Prefers _NET_WM_NAME to get window title in UTF-8 encoding
+fn get_xcb_window_title(
+    ewmh_title: xcb::Result<x::GetPropertyReply>,
+    title: xcb::Result<x::GetPropertyReply>,
+) -> xcb::Result<String> {
+    let window_title = ewmh_title.or(title)?;
+    let window_title = String::from_utf8_lossy(get_xcb_text(&window_title));
     Ok(window_title.into_owned())
 }
 
-fn get_xcb_window_class(conn: &xcb::Connection, window: x::Window) -> xcb::Result<String> {
-    let window_class = conn.send_request(&x::GetProperty {
-        delete: false,
-        window,
-        property: x::ATOM_WM_CLASS,
-        r#type: x::ATOM_STRING,
-        long_offset: 0,
-        long_length: 1024,
-    });
-    let window_class = conn.wait_for_reply(window_class)?;
-    let window_class = window_class.value();
-    let window_class = std::str::from_utf8(window_class);
-    Ok(window_class.unwrap_or("").to_owned())
+fn get_xcb_window_class(reply: &x::GetPropertyReply) -> String {
+    let window_class = std::str::from_utf8(get_xcb_text(reply));
+    window_class.unwrap_or("").to_owned()
 }
 
-fn get_xcb_active_window_atom(conn: &xcb::Connection) -> xcb::Result<x::Atom> {
-    let active_window_id = conn.send_request(&x::InternAtom {
-        only_if_exists: true,
-        name: b"_NET_ACTIVE_WINDOW",
-    });
+// Text property that may be missing, e.g. WM_WINDOW_ROLE
+fn get_xcb_string_property(reply: &x::GetPropertyReply) -> Option<String> {
+    let value = String::from_utf8_lossy(get_xcb_text(reply));
+    let value = value.trim_end_matches('\u{0}');
 
-    Ok(conn.wait_for_reply(active_window_id)?.atom())
+    (!value.is_empty()).then(|| value.to_owned())
 }
 
-fn get_xcb_translated_position(
-    conn: &xcb::Connection,
-    active_window: x::Window,
-) -> xcb::Result<WindowPosition> {
-    let window_geometry = conn.send_request(&x::GetGeometry {
-        drawable: x::Drawable::Window(active_window),
-    });
-    let window_geometry = conn.wait_for_reply(window_geometry)?;
-    let window_geometry_x = window_geometry.x();
-    let window_geometry_y = window_geometry.y();
-
-    let translated_position = conn.send_request(&x::TranslateCoordinates {
-        dst_window: window_geometry.root(),
-        src_window: active_window,
-        src_x: window_geometry_x,
-        src_y: window_geometry_y,
-    });
-    let translated_position = conn.wait_for_reply(translated_position)?;
-
-    Ok(WindowPosition {
-        x: (translated_position.dst_x() - window_geometry_x)
-            .try_into()
-            .unwrap(),
-        y: (translated_position.dst_y() - window_geometry_y)
-            .try_into()
-            .unwrap(),
-        width: window_geometry.width().try_into().unwrap(),
-        height: window_geometry.height().try_into().unwrap(),
-    })
+fn get_xcb_window_state(conn: &X11Connection, reply: &x::GetPropertyReply) -> WindowState {
+    let atoms = &conn.atoms;
+    let flags = [
+        (atoms.net_wm_state_hidden, WindowState::MINIMIZED),
+        (
+            atoms.net_wm_state_maximized_vert,
+            WindowState::MAXIMIZED_VERT,
+        ),
+        (
+            atoms.net_wm_state_maximized_horz,
+            WindowState::MAXIMIZED_HORZ,
+        ),
+        (atoms.net_wm_state_fullscreen, WindowState::FULLSCREEN),
+        (atoms.net_wm_state_above, WindowState::ABOVE),
+        (atoms.net_wm_state_sticky, WindowState::STICKY),
+        (
+            atoms.net_wm_state_demands_attention,
+            WindowState::DEMANDS_ATTENTION,
+        ),
+        (atoms.net_wm_state_modal, WindowState::MODAL),
+    ];
+
+    reply
+        .value::<x::Atom>()
+        .iter()
+        .filter(|atom| **atom != x::ATOM_NONE)
+        .filter_map(|atom| flags.iter().find(|(flag_atom, _)| flag_atom == atom))
+        .fold(WindowState::empty(), |state, (_, flag)| state | *flag)
+}
+
+// The first type the window lists that we know, types are listed in order of preference
+fn get_xcb_window_type(conn: &X11Connection, reply: &x::GetPropertyReply) -> Option<WindowType> {
+    let atoms = &conn.atoms;
+    let types = [
+        (atoms.net_wm_window_type_normal, WindowType::Normal),
+        (atoms.net_wm_window_type_dialog, WindowType::Dialog),
+        (atoms.net_wm_window_type_dock, WindowType::Dock),
+        (atoms.net_wm_window_type_desktop, WindowType::Desktop),
+        (atoms.net_wm_window_type_toolbar, WindowType::Toolbar),
+        (atoms.net_wm_window_type_menu, WindowType::Menu),
+        (atoms.net_wm_window_type_utility, WindowType::Utility),
+        (atoms.net_wm_window_type_splash, WindowType::Splash),
+        (
+            atoms.net_wm_window_type_dropdown_menu,
+            WindowType::DropdownMenu,
+        ),
+        (atoms.net_wm_window_type_popup_menu, WindowType::PopupMenu),
+        (atoms.net_wm_window_type_tooltip, WindowType::Tooltip),
+        (
+            atoms.net_wm_window_type_notification,
+            WindowType::Notification,
+        ),
+        (atoms.net_wm_window_type_combo, WindowType::Combo),
+        (atoms.net_wm_window_type_dnd, WindowType::Dnd),
+    ];
+
+    reply
+        .value::<x::Atom>()
+        .iter()
+        .filter(|atom| **atom != x::ATOM_NONE)
+        .find_map(|atom| {
+            types
+                .iter()
+                .find(|(type_atom, _)| type_atom == atom)
+                .map(|(_, window_type)| *window_type)
+        })
+}
+
+fn get_xcb_transient_for(reply: &x::GetPropertyReply) -> Option<x::Window> {
+    reply
+        .value::<x::Window>()
+        .first()
+        .copied()
+        .filter(|window| !window.is_none())
 }
 
-fn is_wayland() -> bool {
-    env::var("WAYLAND_DISPLAY").is_ok()
+fn get_xcb_window_desktop(reply: &x::GetPropertyReply) -> Option<u32> {
+    // 0xFFFFFFFF puts the window on all desktops
+    reply
+        .value::<u32>()
+        .first()
+        .copied()
+        .filter(|desktop| *desktop != u32::MAX)
 }
 
-pub struct LinuxPlatformApi {}
+fn get_xcb_translated_position(
+    geometry: &x::GetGeometryReply,
+    translated_position: &x::TranslateCoordinatesReply,
+) -> WindowPosition {
+    WindowPosition {
+        x: translated_position.dst_x().into(),
+        y: translated_position.dst_y().into(),
+        width: geometry.width().into(),
+        height: geometry.height().into(),
+    }
+}
 
-impl PlatformApi for LinuxPlatformApi {
-    fn get_position(&self) -> Result<WindowPosition, ()> {
-        let active_winow = self.get_active_window()?;
-        Ok(active_winow.position)
+// Adds the decorations from _NET_FRAME_EXTENTS to the client area. Without it the frame is the
+// top-level window the WM reparented the client into, if any.
+fn get_xcb_frame_rect(
+    conn: &X11Connection,
+    window: x::Window,
+    client_rect: &WindowPosition,
+    frame_extents: &x::GetPropertyReply,
+) -> WindowPosition {
+    match frame_extents.value::<u32>() {
+        [left, right, top, bottom] => WindowPosition {
+            x: client_rect.x - f64::from(*left),
+            y: client_rect.y - f64::from(*top),
+            width: client_rect.width + f64::from(*left) + f64::from(*right),
+            height: client_rect.height + f64::from(*top) + f64::from(*bottom),
+        },
+        _ => get_xcb_reparenting_frame(conn, window)
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| client_rect.clone()),
     }
+}
 
-    fn get_active_window(&self) -> Result<ActiveWindow, ()> {
-        // Try Wayland first if WAYLAND_DISPLAY is set
-        if is_wayland() {
-            if let Some(window) = get_active_window_wayland() {
-                return Ok(window);
-            }
+// Geometry of the child of the root window that contains `window`, `None` if that is the window
+fn get_xcb_reparenting_frame(
+    conn: &X11Connection,
+    window: x::Window,
+) -> Result<Option<WindowPosition>, Error> {
+    let mut frame = window;
+    loop {
+        let tree = conn.wait_for_reply(conn.send_request(&x::QueryTree { window: frame }))?;
+        if tree.parent() == conn.root || tree.parent().is_none() {
+            break;
         }
+        frame = tree.parent();
+    }
+    if frame == window {
+        return Ok(None);
+    }
+
+    // Children of the root are positioned in root coordinates
+    let geometry = conn.wait_for_reply(conn.send_request(&x::GetGeometry {
+        drawable: x::Drawable::Window(frame),
+    }))?;
+    let border = f64::from(geometry.border_width()) * 2.0;
+
+    Ok(Some(WindowPosition {
+        x: geometry.x().into(),
+        y: geometry.y().into(),
+        width: f64::from(geometry.width()) + border,
+        height: f64::from(geometry.height()) + border,
+    }))
+}
+
+pub fn get_xcb_active_window(conn: &X11Connection) -> Result<x::Window, Error> {
+    if conn.atoms.net_active_window == x::ATOM_NONE {
+        return Err(Error::EwmhUnsupported);
+    }
 
-        // Fall back to X11/XCB
-        self.get_active_window_x11()
+    let active_window = request_xcb_property(
+        conn,
+        conn.root,
+        conn.atoms.net_active_window,
+        x::ATOM_WINDOW,
+        1,
+    );
+    let active_window = conn.wait_for_reply(active_window)?;
+
+    active_window
+        .value::<x::Window>()
+        .first()
+        .copied()
+        .filter(|window| !window.is_none())
+        .ok_or(Error::NoActiveWindow)
+}
+
+// Fills in what depends on the rest of the screen: the monitor and the workspace the window is on
+fn place_xcb_window(
+    window: ActiveWindow,
+    monitors: &[Monitor],
+    workspaces: &[Workspace],
+) -> ActiveWindow {
+    let workspace = window.workspace.map(|workspace| {
+        workspaces
+            .iter()
+            .find(|known| known.index == workspace.index)
+            .cloned()
+            .unwrap_or(workspace)
+    });
+
+    ActiveWindow {
+        monitor: find_monitor(monitors, &window.position),
+        workspace,
+        ..window
     }
 }
 
-impl LinuxPlatformApi {
-    fn get_active_window_x11(&self) -> Result<ActiveWindow, ()> {
-        let (conn, _) = xcb::Connection::connect(None).map_err(|_| ())?;
-        let setup = conn.get_setup();
+// Reads a single window, together with the monitor and workspace it is on
+pub fn get_xcb_window(conn: &X11Connection, window: x::Window) -> Result<ActiveWindow, Error> {
+    let cookies = XcbWindowCookies::send(conn, window);
+    let workspaces = XcbWorkspaceCookies::send(conn);
+    let monitors = get_xcb_monitors(conn);
+    let window = cookies.wait(conn);
+    let workspaces = workspaces.wait(conn).unwrap_or_default();
 
-        let xcb_active_window_atom = get_xcb_active_window_atom(&conn).map_err(|_| ())?;
-        if xcb_active_window_atom == x::ATOM_NONE {
-            // EWMH not supported
-            return Err(());
-        }
+    Ok(place_xcb_window(window?, &monitors, &workspaces))
+}
 
-        let root_window = setup.roots().next();
-        if root_window.is_none() {
-            return Err(());
-        }
-        let root_window = root_window.unwrap().root();
-
-        let active_window = conn.send_request(&x::GetProperty {
-            delete: false,
-            window: root_window,
-            property: xcb_active_window_atom,
-            r#type: x::ATOM_WINDOW,
-            long_offset: 0,
-            long_length: 1,
-        });
-        let active_window = conn.wait_for_reply(active_window).map_err(|_| ())?;
-        let active_window = active_window.value::<x::Window>().get(0);
-        if active_window.is_none() {
-            return Err(());
+fn list_xcb_windows(conn: &X11Connection) -> Result<Vec<ActiveWindow>, Error> {
+    if conn.atoms.net_client_list == x::ATOM_NONE {
+        return Err(Error::EwmhUnsupported);
+    }
+
+    let active_window = match get_xcb_active_window(conn) {
+        Ok(active_window) => Some(active_window),
+        Err(Error::NoActiveWindow) => None,
+        Err(err) => return Err(err),
+    };
+
+    let client_list = request_xcb_property(
+        conn,
+        conn.root,
+        conn.atoms.net_client_list,
+        x::ATOM_WINDOW,
+        u32::MAX,
+    );
+    let client_list = conn.wait_for_reply(client_list)?;
+
+    let cookies = client_list
+        .value::<x::Window>()
+        .iter()
+        .map(|window| XcbWindowCookies::send(conn, *window))
+        .collect::<Vec<_>>();
+    let workspaces = XcbWorkspaceCookies::send(conn);
+    let monitors = get_xcb_monitors(conn);
+    let workspaces = workspaces.wait(conn).unwrap_or_default();
+
+    // Waits on every window before giving up on one, see `XcbWindowCookies::wait`
+    let results = cookies
+        .into_iter()
+        .map(|cookies| (cookies.window, cookies.wait(conn)))
+        .collect::<Vec<_>>();
+
+    let mut windows = Vec::with_capacity(results.len());
+    for (window, result) in results {
+        let is_active = Some(window) == active_window;
+
+        match result {
+            Ok(window) => windows.push(ActiveWindow {
+                is_active,
+                ..place_xcb_window(window, &monitors, &workspaces)
+            }),
+            // The window was destroyed after _NET_CLIENT_LIST was read
+            Err(err) if is_bad_window(&err) => continue,
+            Err(err) => return Err(err),
         }
-        let active_window = active_window.unwrap();
+    }
 
-        let window_pid: u32 = get_xcb_window_pid(&conn, *active_window).map_err(|_| ())?;
-        let position = get_xcb_translated_position(&conn, *active_window).map_err(|_| ())?;
-        let title = get_xcb_window_title(&conn, *active_window).map_err(|_| ())?;
-        let window_class = get_xcb_window_class(&conn, *active_window).map_err(|_| ())?;
+    Ok(windows)
+}
 
-        let mut process_name = window_class
-            .split('\u{0}')
-            .filter(|str| !str.is_empty())
-            .collect::<Vec<&str>>();
-        let process_name = process_name.pop().unwrap_or("").to_owned();
+fn is_x11() -> bool {
+    env::var("DISPLAY").is_ok()
+}
 
-        let process_path = read_link(format!("/proc/{}/exe", window_pid));
+#[derive(Default)]
+pub struct X11PlatformApi {
+    x11: Mutex<Option<X11Connection>>,
+}
 
-        Ok(ActiveWindow {
-            process_id: window_pid.try_into().unwrap(),
-            window_id: active_window.resource_id().to_string(),
-            app_name: process_name,
-            position,
-            title,
-            process_path: process_path.unwrap_or_default(),
+impl PlatformApi for X11PlatformApi {
+    fn backend(&self) -> Backend {
+        Backend::X11
+    }
+
+    fn is_available(&self) -> bool {
+        is_x11()
+    }
+
+    fn get_active_window(&self) -> Result<ActiveWindow, Error> {
+        self.with_x11(|conn| {
+            let active_window = get_xcb_active_window(conn)?;
+            let window = get_xcb_window(conn, active_window)?;
+
+            Ok(ActiveWindow {
+                is_active: true,
+                ..window
+            })
         })
     }
+
+    fn list_windows(&self) -> Result<Vec<ActiveWindow>, Error> {
+        self.with_x11(list_xcb_windows)
+    }
+
+    fn list_monitors(&self) -> Result<Vec<Monitor>, Error> {
+        self.with_x11(list_xcb_monitors)
+    }
+
+    fn workspaces(&self) -> Result<Vec<Workspace>, Error> {
+        self.with_x11(list_xcb_workspaces)
+    }
+
+    fn watch(&self) -> Result<Box<dyn WindowEvents>, Error> {
+        if !is_x11() {
+            return Err(Error::NoDisplayServer);
+        }
+
+        // The watcher blocks on its connection, so it gets one of its own
+        let events = XcbWindowEvents::new(X11Connection::connect()?)?;
+        Ok(Box::new(FdWindowEvents::new(events)?))
+    }
+
+    fn idle_time(&self) -> Result<Duration, Error> {
+        self.with_x11(get_xcb_idle_time)
+    }
+
+    fn get_window_icons(&self, window_id: &WindowId) -> Result<Vec<WindowIcon>, Error> {
+        self.with_x11(|conn| get_xcb_window_icons(conn, window_id))
+    }
+
+    fn capture_window(&self, window_id: &WindowId, area: CaptureArea) -> Result<Screenshot, Error> {
+        self.with_x11(|conn| capture_xcb_window(conn, window_id, area))
+    }
+
+    fn activate_window(&self, window_id: &WindowId) -> Result<(), Error> {
+        self.with_x11(|conn| activate_xcb_window(conn, window_id))
+    }
+
+    fn move_resize_window(
+        &self,
+        window_id: &WindowId,
+        position: &WindowPosition,
+    ) -> Result<(), Error> {
+        self.with_x11(|conn| move_resize_xcb_window(conn, window_id, position))
+    }
+
+    fn set_window_state(
+        &self,
+        window_id: &WindowId,
+        state: WindowState,
+        enable: bool,
+    ) -> Result<(), Error> {
+        self.with_x11(|conn| set_xcb_window_state(conn, window_id, state, enable))
+    }
+
+    fn close_window(&self, window_id: &WindowId) -> Result<(), Error> {
+        self.with_x11(|conn| close_xcb_window(conn, window_id))
+    }
+}
+
+impl X11PlatformApi {
+    // Runs `f` on the cached X11 connection, connecting first if needed.
+    // A connection that went bad is dropped so the next call reconnects.
+    fn with_x11<T>(&self, f: impl FnOnce(&X11Connection) -> Result<T, Error>) -> Result<T, Error> {
+        let mut x11 = self.x11.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let conn = match x11.take() {
+            Some(conn) => conn,
+            None if !is_x11() => return Err(Error::NoDisplayServer),
+            None => X11Connection::connect()?,
+        };
+        conn.handle_queued_events();
+        let result = f(&conn);
+        if !conn.is_broken() {
+            *x11 = Some(conn);
+        }
+
+        result
+    }
 }
@@ -0,0 +1,134 @@
+use std::ffi::CStr;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{Error, ProcessInfo};
+
+fn proc_error(err: io::Error) -> Error {
+    match err.kind() {
+        io::ErrorKind::PermissionDenied => Error::PermissionDenied,
+        _ => Error::Platform(err.into()),
+    }
+}
+
+// Fields of /proc/<pid>/stat after the command name, which is in parentheses and may hold
+// spaces, so the first entry is field 3 (the state)
+fn stat_fields(stat: &str) -> Vec<&str> {
+    stat.rsplit_once(')')
+        .map(|(_, fields)| fields.split_whitespace().collect())
+        .unwrap_or_default()
+}
+
+// Arguments in /proc/<pid>/cmdline are NUL-terminated. Empty arguments are kept,
+// only the terminator of the last one must not add another.
+fn cmdline_args(cmdline: &[u8]) -> Vec<String> {
+    let cmdline = cmdline.strip_suffix(&[0]).unwrap_or(cmdline);
+    if cmdline.is_empty() {
+        return Vec::new();
+    }
+
+    cmdline
+        .split(|byte| *byte == 0)
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect()
+}
+
+// Boot time from /proc/stat, process start times are counted from it
+fn boot_time() -> Option<SystemTime> {
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+    let btime = stat
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))?
+        .trim()
+        .parse()
+        .ok()?;
+
+    Some(UNIX_EPOCH + Duration::from_secs(btime))
+}
+
+fn start_time(start_ticks: &str) -> Option<SystemTime> {
+    let start_ticks = start_ticks.parse::<u64>().ok()?;
+    let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks_per_second <= 0 {
+        return None;
+    }
+
+    let since_boot = Duration::from_secs_f64(start_ticks as f64 / ticks_per_second as f64);
+    Some(boot_time()? + since_boot)
+}
+
+// Real user id from the `Uid:` line of /proc/<pid>/status
+fn status_uid(status: &str) -> Option<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn username(uid: u32) -> Option<String> {
+    let mut passwd = unsafe { std::mem::zeroed::<libc::passwd>() };
+    let mut buf = vec![0 as libc::c_char; 1024];
+    let mut result = std::ptr::null_mut();
+
+    loop {
+        let err =
+            unsafe { libc::getpwuid_r(uid, &mut passwd, buf.as_mut_ptr(), buf.len(), &mut result) };
+        if err != libc::ERANGE {
+            break;
+        }
+        buf.resize(buf.len() * 2, 0);
+    }
+
+    if result.is_null() {
+        return None;
+    }
+    let name = unsafe { CStr::from_ptr(passwd.pw_name) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+pub fn read_process_info(pid: u64) -> Result<ProcessInfo, Error> {
+    let proc_dir = PathBuf::from(format!("/proc/{}", pid));
+
+    // The only file every process lets us read, it tells whether the process exists
+    let stat = fs::read_to_string(proc_dir.join("stat")).map_err(proc_error)?;
+    let stat = stat_fields(&stat);
+
+    let cmdline = fs::read(proc_dir.join("cmdline"))
+        .map(|cmdline| cmdline_args(&cmdline))
+        .unwrap_or_default();
+    let uid = fs::read_to_string(proc_dir.join("status"))
+        .ok()
+        .and_then(|status| status_uid(&status));
+    // The kernel marks the link target of an executable that is gone
+    let exe_deleted = fs::read_link(proc_dir.join("exe"))
+        .is_ok_and(|exe| exe.to_string_lossy().ends_with(" (deleted)"));
+
+    Ok(ProcessInfo {
+        pid,
+        cmdline,
+        cwd: fs::read_link(proc_dir.join("cwd")).ok(),
+        parent_pid: stat.get(1).and_then(|ppid| ppid.parse().ok()),
+        uid,
+        username: uid.and_then(username),
+        start_time: stat.get(19).and_then(|ticks| start_time(ticks)),
+        exe_deleted,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cmdline_keeps_empty_arguments() {
+        assert_eq!(cmdline_args(b"sh\0-c\0\0"), ["sh", "-c", ""]);
+        assert_eq!(cmdline_args(b"sh\0\0-x\0"), ["sh", "", "-x"]);
+        assert_eq!(cmdline_args(b"kthreadd"), ["kthreadd"]);
+        assert!(cmdline_args(b"").is_empty());
+    }
+}
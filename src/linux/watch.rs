@@ -0,0 +1,330 @@
+use std::io::{Read, Write};
+use std::os::fd::{AsRawFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+
+use xcb::x;
+
+use super::connection::X11Connection;
+use super::platform_api::{get_xcb_active_window, get_xcb_window};
+use crate::common::watcher::{StopHandle, WakeFn, WindowEvents};
+use crate::{ActiveWindow, Error, WindowId};
+
+// Short-lived, reports are moved out right away
+#[allow(clippy::large_enum_variant)]
+pub enum Step {
+    Report(Result<ActiveWindow, Error>),
+    // Nothing to report until the file descriptor becomes readable
+    Wait,
+    Done,
+}
+
+// Event source that is driven by a single readable file descriptor
+pub trait FdEventSource: Send {
+    fn fd(&self) -> RawFd;
+
+    // Handles whatever has been received so far without blocking
+    fn step(&mut self) -> Step;
+}
+
+// Remembers the id and title reported last, so events that change neither are dropped
+#[derive(Default)]
+pub struct LastReported(Option<Result<(WindowId, String), ()>>);
+
+impl LastReported {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    pub fn update(
+        &mut self,
+        result: Result<ActiveWindow, Error>,
+    ) -> Option<Result<ActiveWindow, Error>> {
+        let current = result
+            .as_ref()
+            .map(|window| (window.window_id.clone(), window.title.clone()))
+            .map_err(|_| ());
+
+        if self.0.as_ref() == Some(&current) {
+            return None;
+        }
+        self.0 = Some(current);
+
+        Some(result.map(|window| ActiveWindow {
+            is_active: true,
+            ..window
+        }))
+    }
+}
+
+// Lets a thread blocked in `wait` be woken up from another thread
+struct Wakeup {
+    rx: UnixStream,
+    tx: Arc<UnixStream>,
+}
+
+impl Wakeup {
+    fn new() -> Result<Self, Error> {
+        let (rx, tx) = UnixStream::pair().map_err(|err| Error::Platform(err.into()))?;
+        rx.set_nonblocking(true)
+            .map_err(|err| Error::Platform(err.into()))?;
+
+        Ok(Self {
+            rx,
+            tx: Arc::new(tx),
+        })
+    }
+
+    fn wake_fn(&self) -> WakeFn {
+        let tx = self.tx.clone();
+
+        Box::new(move || {
+            let _ = (&*tx).write(&[0]);
+        })
+    }
+
+    // Blocks until `fd` is readable or the wake function is called
+    fn wait(&self, fd: RawFd) -> Result<(), Error> {
+        let mut fds = [
+            libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            },
+            libc::pollfd {
+                fd: self.rx.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            },
+        ];
+
+        let result = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) };
+        if result < 0 {
+            let err = std::io::Error::last_os_error();
+            if err.kind() != std::io::ErrorKind::Interrupted {
+                return Err(Error::Platform(err.into()));
+            }
+        }
+
+        let mut buf = [0u8; 16];
+        while let Ok(read) = (&self.rx).read(&mut buf) {
+            if read == 0 {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// File descriptor owned by an event source, registered with the async reactor
+#[cfg(feature = "async")]
+struct BorrowedRawFd(RawFd);
+
+#[cfg(feature = "async")]
+impl std::os::fd::AsFd for BorrowedRawFd {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        // The event source keeps the descriptor open for as long as this is registered
+        unsafe { std::os::fd::BorrowedFd::borrow_raw(self.0) }
+    }
+}
+
+pub struct FdWindowEvents<S> {
+    // Declared before `source` so it is deregistered before the descriptor is closed
+    #[cfg(feature = "async")]
+    readable: Option<async_io::Async<BorrowedRawFd>>,
+    source: S,
+    wakeup: Wakeup,
+}
+
+impl<S: FdEventSource> FdWindowEvents<S> {
+    pub fn new(source: S) -> Result<Self, Error> {
+        Ok(Self {
+            #[cfg(feature = "async")]
+            readable: None,
+            source,
+            wakeup: Wakeup::new()?,
+        })
+    }
+}
+
+impl<S: FdEventSource> WindowEvents for FdWindowEvents<S> {
+    fn next_change(&mut self, stop: &StopHandle) -> Option<Result<ActiveWindow, Error>> {
+        loop {
+            if stop.is_stopped() {
+                return None;
+            }
+
+            match self.source.step() {
+                Step::Report(result) => return Some(result),
+                Step::Wait => {
+                    if let Err(err) = self.wakeup.wait(self.source.fd()) {
+                        return Some(Err(err));
+                    }
+                }
+                Step::Done => return None,
+            }
+        }
+    }
+
+    fn waker(&self) -> WakeFn {
+        self.wakeup.wake_fn()
+    }
+
+    #[cfg(feature = "async")]
+    fn poll_change(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<ActiveWindow, Error>>> {
+        loop {
+            match self.source.step() {
+                Step::Report(result) => return Poll::Ready(Some(result)),
+                Step::Wait => {}
+                Step::Done => return Poll::Ready(None),
+            }
+
+            let readable = match &mut self.readable {
+                Some(readable) => readable,
+                readable => match async_io::Async::new(BorrowedRawFd(self.source.fd())) {
+                    Ok(registered) => readable.insert(registered),
+                    Err(err) => return Poll::Ready(Some(Err(Error::Platform(err.into())))),
+                },
+            };
+
+            match readable.poll_readable(cx) {
+                Poll::Ready(Ok(())) => continue,
+                Poll::Ready(Err(err)) => {
+                    return Poll::Ready(Some(Err(Error::Platform(err.into()))))
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+// Follows _NET_ACTIVE_WINDOW on the root window and the title of the focused window
+// through PropertyNotify events, so nothing is queried until something changes
+pub struct XcbWindowEvents {
+    conn: X11Connection,
+    // Focused window we currently receive PropertyNotify events from
+    watched_window: Option<x::Window>,
+    last_reported: LastReported,
+}
+
+impl XcbWindowEvents {
+    pub fn new(conn: X11Connection) -> Result<Self, Error> {
+        if conn.atoms.net_active_window == x::ATOM_NONE {
+            return Err(Error::EwmhUnsupported);
+        }
+
+        conn.send_and_check_request(&x::ChangeWindowAttributes {
+            window: conn.root,
+            value_list: &[x::Cw::EventMask(x::EventMask::PROPERTY_CHANGE)],
+        })?;
+
+        Ok(Self {
+            conn,
+            watched_window: None,
+            last_reported: LastReported::default(),
+        })
+    }
+
+    fn is_relevant(&self, event: &xcb::Event) -> bool {
+        match event {
+            xcb::Event::X(x::Event::PropertyNotify(event)) => {
+                if event.window() == self.conn.root {
+                    event.atom() == self.conn.atoms.net_active_window
+                } else {
+                    Some(event.window()) == self.watched_window
+                        && (event.atom() == self.conn.atoms.net_wm_name
+                            || event.atom() == x::ATOM_WM_NAME)
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn watch_window(&mut self, window: Option<x::Window>) {
+        if window == self.watched_window {
+            return;
+        }
+
+        let windows = [
+            (self.watched_window, x::EventMask::empty()),
+            (window, x::EventMask::PROPERTY_CHANGE),
+        ];
+        for (window, event_mask) in windows {
+            if let Some(window) = window {
+                // Errors for windows that are already gone are skipped in `read_events`
+                self.conn.send_request(&x::ChangeWindowAttributes {
+                    window,
+                    value_list: &[x::Cw::EventMask(event_mask)],
+                });
+            }
+        }
+
+        self.watched_window = window;
+    }
+
+    // Re-reads the active window, returns `None` if nothing we report on has changed
+    fn refresh(&mut self) -> Option<Result<ActiveWindow, Error>> {
+        // Start listening to the new window before reading its title to not miss a change
+        let active_window = get_xcb_active_window(&self.conn);
+        self.watch_window(active_window.as_ref().ok().copied());
+
+        let result = active_window.and_then(|window| get_xcb_window(&self.conn, window));
+        self.last_reported.update(result)
+    }
+
+    // Drains queued events and reports whether any of them needs a refresh
+    fn read_events(&self) -> Result<bool, Error> {
+        let mut changed = false;
+
+        loop {
+            match self.conn.poll_for_event() {
+                Ok(Some(event)) => {
+                    self.conn.handle_event(&event);
+                    changed |= self.is_relevant(&event);
+                }
+                Ok(None) => return Ok(changed),
+                // A watched window was destroyed before its event mask was changed
+                Err(xcb::Error::Protocol(_)) => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+impl FdEventSource for XcbWindowEvents {
+    fn fd(&self) -> RawFd {
+        self.conn.as_raw_fd()
+    }
+
+    fn step(&mut self) -> Step {
+        if self.last_reported.is_empty() {
+            if let Some(result) = self.refresh() {
+                return Step::Report(result);
+            }
+        }
+
+        loop {
+            if self.conn.is_broken() {
+                return Step::Done;
+            }
+
+            match self.read_events() {
+                Ok(true) => {
+                    if let Some(result) = self.refresh() {
+                        return Step::Report(result);
+                    }
+                }
+                Ok(false) => {
+                    return match self.conn.flush() {
+                        Ok(()) => Step::Wait,
+                        Err(err) => Step::Report(Err(err.into())),
+                    }
+                }
+                Err(err) => return Step::Report(Err(err)),
+            }
+        }
+    }
+}
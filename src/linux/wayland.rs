@@ -1,28 +1,361 @@
+use std::env;
 use std::fs::read_link;
+use std::os::fd::RawFd;
 
-use crate::{ActiveWindow, WindowPosition};
+use serde_json::Value;
 
-fn try_kwin() -> Option<ActiveWindow> {
-    // Use kdotool library to get active window info
-    let info = kdotool::get_active_window_info().ok()?;
+use super::kwin::{run_script, KWinScript};
+use super::watch::{FdEventSource, FdWindowEvents, LastReported, Step};
+use crate::common::{platform_api::PlatformApi, watcher::WindowEvents};
+use crate::{
+    ActiveWindow, Backend, CoordinateSpace, Error, Monitor, WindowId, WindowPosition, WindowState,
+    WindowType, Workspace,
+};
 
-    let process_path = read_link(format!("/proc/{}/exe", info.pid)).unwrap_or_default();
+fn get_kwin_active_window() -> Result<ActiveWindow, Error> {
+    let window = run_script(
+        r#"
+    const w = workspace_activeWindow();
+    output_result(w ? window_info(w) : null);
+"#,
+    )?;
 
-    Some(ActiveWindow {
-        title: info.title,
-        app_name: info.class_name,
-        window_id: info.id,
-        process_id: info.pid as u64,
+    match window {
+        Value::Null => Err(Error::NoActiveWindow),
+        window => Ok(kwin_window_from_json(&window)),
+    }
+}
+
+// Only the name, geometry and scale of outputs are available to KWin scripts
+fn kwin_monitor_from_json(output: &Value) -> Option<Monitor> {
+    output.is_object().then(|| Monitor {
+        name: output["name"].as_str().unwrap_or_default().to_owned(),
+        position: kwin_rect_from_json(output),
+        scale_factor: output["scale"].as_f64().unwrap_or(1.0),
+        coordinate_space: CoordinateSpace::Logical,
+        ..Default::default()
+    })
+}
+
+// Names used by `window_state` in the KWin script header
+const KWIN_STATE_FLAGS: [(&str, WindowState); 8] = [
+    ("minimized", WindowState::MINIMIZED),
+    ("maximized_vert", WindowState::MAXIMIZED_VERT),
+    ("maximized_horz", WindowState::MAXIMIZED_HORZ),
+    ("fullscreen", WindowState::FULLSCREEN),
+    ("above", WindowState::ABOVE),
+    ("sticky", WindowState::STICKY),
+    ("demands_attention", WindowState::DEMANDS_ATTENTION),
+    ("modal", WindowState::MODAL),
+];
+
+fn kwin_state_from_json(state: &Value) -> WindowState {
+    KWIN_STATE_FLAGS
+        .iter()
+        .filter(|(name, _)| state[name].as_bool().unwrap_or_default())
+        .fold(WindowState::empty(), |state, (_, flag)| state | *flag)
+}
+
+// Names used by `window_type` in the KWin script header
+fn kwin_type_from_json(window_type: &Value) -> WindowType {
+    match window_type.as_str().unwrap_or_default() {
+        "dialog" => WindowType::Dialog,
+        "dock" => WindowType::Dock,
+        "desktop" => WindowType::Desktop,
+        "toolbar" => WindowType::Toolbar,
+        "menu" => WindowType::Menu,
+        "utility" => WindowType::Utility,
+        "splash" => WindowType::Splash,
+        "dropdown_menu" => WindowType::DropdownMenu,
+        "popup_menu" => WindowType::PopupMenu,
+        "tooltip" => WindowType::Tooltip,
+        "notification" => WindowType::Notification,
+        "combo" => WindowType::Combo,
+        "dnd" => WindowType::Dnd,
+        _ => WindowType::Normal,
+    }
+}
+
+// See `workspace_desktopList` in the KWin script header
+fn kwin_workspace_from_json(desktop: &Value) -> Option<Workspace> {
+    desktop.is_object().then(|| Workspace {
+        index: desktop["index"].as_u64().unwrap_or_default() as u32,
+        name: desktop["name"].as_str().unwrap_or_default().to_owned(),
+        is_current: desktop["current"].as_bool().unwrap_or_default(),
+    })
+}
+
+fn kwin_rect_from_json(rect: &Value) -> WindowPosition {
+    let f64_field = |name: &str| rect[name].as_f64().unwrap_or_default();
+
+    WindowPosition::new(
+        f64_field("x"),
+        f64_field("y"),
+        f64_field("width"),
+        f64_field("height"),
+    )
+}
+
+// See `window_info` in the KWin script header
+fn kwin_window_from_json(window: &Value) -> ActiveWindow {
+    let str_field = |name: &str| window[name].as_str().unwrap_or_default().to_owned();
+    let frame_rect = kwin_rect_from_json(window);
+
+    let process_id = window["pid"].as_u64().unwrap_or_default();
+    let process_path = read_link(format!("/proc/{}/exe", process_id)).unwrap_or_default();
+
+    ActiveWindow {
+        title: str_field("title"),
+        app_name: str_field("class_name"),
+        wm_instance: str_field("instance_name"),
+        wm_class: str_field("class_name"),
+        // GTK application ids are only set on X11 windows
+        gtk_application_id: None,
+        window_role: Some(str_field("role")).filter(|role| !role.is_empty()),
+        window_id: WindowId::KWin(str_field("id")),
+        process_id,
         process_path,
-        position: WindowPosition {
-            x: info.x,
-            y: info.y,
-            width: info.width,
-            height: info.height,
+        client_rect: match &window["client"] {
+            Value::Null => frame_rect.clone(),
+            client => kwin_rect_from_json(client),
         },
-    })
+        position: frame_rect.clone(),
+        frame_rect,
+        is_active: window["active"].as_bool().unwrap_or_default(),
+        backend: Backend::KWin,
+        monitor: kwin_monitor_from_json(&window["output"]),
+        coordinate_space: CoordinateSpace::Logical,
+        state: kwin_state_from_json(&window["state"]),
+        window_type: kwin_type_from_json(&window["type"]),
+        transient_for: window["transient_for"]
+            .as_str()
+            .map(|id| WindowId::KWin(id.to_owned())),
+        workspace: kwin_workspace_from_json(&window["desktop"]),
+    }
+}
+
+fn list_kwin_monitors() -> Result<Vec<Monitor>, Error> {
+    let outputs = run_script(
+        r#"
+    output_result(workspace_outputList());
+"#,
+    )?;
+
+    Ok(outputs
+        .as_array()
+        .map(|outputs| outputs.iter().filter_map(kwin_monitor_from_json).collect())
+        .unwrap_or_default())
 }
 
-pub fn get_active_window_wayland() -> Option<ActiveWindow> {
-    try_kwin()
+// Runs `action` in a script where `w` is the window with the given id
+fn run_kwin_window_action(window_id: &WindowId, action: &str) -> Result<(), Error> {
+    let WindowId::KWin(id) = window_id else {
+        return Err(Error::InvalidWindowId(window_id.to_string()));
+    };
+
+    let found = run_script(&format!(
+        r#"
+    const w = window_byId({});
+    if (w) {{
+        {}
+    }}
+    output_result(w !== null);
+"#,
+        Value::from(id.as_str()),
+        action
+    ))?;
+
+    match found {
+        Value::Bool(true) => Ok(()),
+        _ => Err(Error::WindowNotFound(window_id.clone())),
+    }
+}
+
+fn set_kwin_window_state(
+    window_id: &WindowId,
+    state: WindowState,
+    enable: bool,
+) -> Result<(), Error> {
+    let flags = KWIN_STATE_FLAGS
+        .iter()
+        .filter(|(_, flag)| state.contains(*flag))
+        .map(|(name, _)| (name.to_string(), Value::Bool(enable)))
+        .collect::<serde_json::Map<_, _>>();
+
+    run_kwin_window_action(
+        window_id,
+        &format!("window_setState(w, {});", Value::Object(flags)),
+    )
+}
+
+fn list_kwin_workspaces() -> Result<Vec<Workspace>, Error> {
+    let desktops = run_script(
+        r#"
+    output_result(workspace_desktopList());
+"#,
+    )?;
+
+    Ok(desktops
+        .as_array()
+        .map(|desktops| {
+            desktops
+                .iter()
+                .filter_map(kwin_workspace_from_json)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+fn list_kwin_windows() -> Result<Vec<ActiveWindow>, Error> {
+    let windows = run_script(
+        r#"
+    output_result(workspace_windowList()
+        .filter((w) => w.normalWindow || w.dialog)
+        .map(window_info));
+"#,
+    )?;
+
+    Ok(windows
+        .as_array()
+        .map(|windows| windows.iter().map(kwin_window_from_json).collect())
+        .unwrap_or_default())
+}
+
+// Keeps a script running in KWin that reports every activation and title change of the active window
+struct KWinWindowEvents {
+    script: KWinScript,
+    last_reported: LastReported,
+    failed: bool,
+}
+
+impl KWinWindowEvents {
+    fn new() -> Result<Self, Error> {
+        let script = KWinScript::load(
+            r#"
+    let watched = null;
+
+    function report() {
+        const w = workspace_activeWindow();
+        output_result(w ? window_info(w) : null);
+    }
+
+    function on_activated(w) {
+        if (watched) {
+            watched.captionChanged.disconnect(report);
+        }
+        watched = w;
+        if (w) {
+            w.captionChanged.connect(report);
+        }
+        report();
+    }
+
+    workspace_windowActivated().connect(on_activated);
+    on_activated(workspace_activeWindow());
+"#,
+        )?;
+
+        Ok(Self {
+            script,
+            last_reported: LastReported::default(),
+            failed: false,
+        })
+    }
+}
+
+impl FdEventSource for KWinWindowEvents {
+    fn fd(&self) -> RawFd {
+        self.script.fd()
+    }
+
+    fn step(&mut self) -> Step {
+        if self.failed {
+            return Step::Done;
+        }
+
+        loop {
+            let result = match self.script.try_next() {
+                Ok(Some(Value::Null)) => Err(Error::NoActiveWindow),
+                Ok(Some(window)) => Ok(kwin_window_from_json(&window)),
+                Ok(None) => return Step::Wait,
+                // The script or the D-Bus connection is gone, nothing more will arrive
+                Err(err) => {
+                    self.failed = true;
+                    return Step::Report(Err(err));
+                }
+            };
+
+            if let Some(result) = self.last_reported.update(result) {
+                return Step::Report(result);
+            }
+        }
+    }
+}
+
+fn is_wayland() -> bool {
+    env::var("WAYLAND_DISPLAY").is_ok()
+}
+
+pub struct KWinPlatformApi;
+
+impl PlatformApi for KWinPlatformApi {
+    fn backend(&self) -> Backend {
+        Backend::KWin
+    }
+
+    fn is_available(&self) -> bool {
+        is_wayland()
+    }
+
+    fn get_active_window(&self) -> Result<ActiveWindow, Error> {
+        get_kwin_active_window()
+    }
+
+    fn list_windows(&self) -> Result<Vec<ActiveWindow>, Error> {
+        list_kwin_windows()
+    }
+
+    fn list_monitors(&self) -> Result<Vec<Monitor>, Error> {
+        list_kwin_monitors()
+    }
+
+    fn workspaces(&self) -> Result<Vec<Workspace>, Error> {
+        list_kwin_workspaces()
+    }
+
+    fn watch(&self) -> Result<Box<dyn WindowEvents>, Error> {
+        Ok(Box::new(FdWindowEvents::new(KWinWindowEvents::new()?)?))
+    }
+
+    fn activate_window(&self, window_id: &WindowId) -> Result<(), Error> {
+        run_kwin_window_action(window_id, "workspace_activateWindow(w);")
+    }
+
+    fn move_resize_window(
+        &self,
+        window_id: &WindowId,
+        position: &WindowPosition,
+    ) -> Result<(), Error> {
+        position.check_geometry()?;
+        run_kwin_window_action(
+            window_id,
+            &format!(
+                "window_setGeometry(w, {}, {}, {}, {});",
+                position.x, position.y, position.width, position.height
+            ),
+        )
+    }
+
+    fn set_window_state(
+        &self,
+        window_id: &WindowId,
+        state: WindowState,
+        enable: bool,
+    ) -> Result<(), Error> {
+        set_kwin_window_state(window_id, state, enable)
+    }
+
+    fn close_window(&self, window_id: &WindowId) -> Result<(), Error> {
+        run_kwin_window_action(window_id, "w.closeWindow();")
+    }
 }
@@ -0,0 +1,78 @@
+use xcb::x;
+
+use super::connection::X11Connection;
+use super::platform_api::request_xcb_property;
+use crate::{Error, Workspace};
+
+// Root window properties describing the desktops, requested together
+pub struct XcbWorkspaceCookies {
+    count: x::GetPropertyCookie,
+    current: x::GetPropertyCookie,
+    names: x::GetPropertyCookie,
+}
+
+impl XcbWorkspaceCookies {
+    pub fn send(conn: &X11Connection) -> Self {
+        let atoms = &conn.atoms;
+
+        Self {
+            count: request_xcb_property(
+                conn,
+                conn.root,
+                atoms.net_number_of_desktops,
+                x::ATOM_CARDINAL,
+                1,
+            ),
+            current: request_xcb_property(
+                conn,
+                conn.root,
+                atoms.net_current_desktop,
+                x::ATOM_CARDINAL,
+                1,
+            ),
+            names: request_xcb_property(
+                conn,
+                conn.root,
+                atoms.net_desktop_names,
+                x::ATOM_ANY,
+                u32::MAX,
+            ),
+        }
+    }
+
+    pub fn wait(self, conn: &X11Connection) -> Result<Vec<Workspace>, Error> {
+        // Every reply is taken before bailing out on an error, see `XcbWindowCookies::wait`
+        let count = conn.wait_for_reply(self.count);
+        let current = conn.wait_for_reply(self.current);
+        let names = conn.wait_for_reply(self.names);
+        let (count, current, names) = (count?, current?, names?);
+
+        let count = *count
+            .value::<u32>()
+            .first()
+            .ok_or(Error::PropertyMissing("_NET_NUMBER_OF_DESKTOPS"))?;
+        let current = current.value::<u32>().first().copied();
+        // Names are NUL-terminated and may be fewer than the desktops.
+        // The property can be set by any client, text in another format is ignored.
+        let names = if names.format() == 8 {
+            names.value::<u8>()
+        } else {
+            &[]
+        };
+        let mut names = names
+            .split(|byte| *byte == 0)
+            .map(|name| String::from_utf8_lossy(name).into_owned());
+
+        Ok((0..count)
+            .map(|index| Workspace {
+                index,
+                name: names.next().unwrap_or_default(),
+                is_current: Some(index) == current,
+            })
+            .collect())
+    }
+}
+
+pub fn list_xcb_workspaces(conn: &X11Connection) -> Result<Vec<Workspace>, Error> {
+    XcbWorkspaceCookies::send(conn).wait(conn)
+}
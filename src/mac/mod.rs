@@ -2,9 +2,11 @@ mod core_graphics_patch;
 mod platform_api;
 mod window_position;
 
+use std::sync::Arc;
+
 use crate::common::platform_api::PlatformApi;
 use platform_api::MacPlatformApi;
 
-pub fn init_platform_api() -> impl PlatformApi {
-    MacPlatformApi {}
+pub fn builtin_backends() -> Vec<Arc<dyn PlatformApi>> {
+    vec![Arc::new(MacPlatformApi {})]
 }
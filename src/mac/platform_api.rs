@@ -1,7 +1,14 @@
 use super::core_graphics_patch::CGRectMakeWithDictionaryRepresentation;
 use super::window_position::FromCgRect;
 use crate::common::{
-    active_window::ActiveWindow, platform_api::PlatformApi, window_position::WindowPosition,
+    active_window::ActiveWindow,
+    backend::Backend,
+    error::Error,
+    platform_api::PlatformApi,
+    window_id::WindowId,
+    window_position::{CoordinateSpace, WindowPosition},
+    window_state::WindowState,
+    window_type::WindowType,
 };
 use appkit_nsworkspace_bindings::{INSRunningApplication, INSWorkspace, NSWorkspace, INSURL};
 use core_foundation::{
@@ -36,15 +43,11 @@ enum DictEntryValue {
 pub struct MacPlatformApi {}
 
 impl PlatformApi for MacPlatformApi {
-    fn get_position(&self) -> Result<WindowPosition, ()> {
-        if let Ok(active_window) = self.get_active_window() {
-            return Ok(active_window.position);
-        }
-
-        Err(())
+    fn backend(&self) -> Backend {
+        Backend::MacOS
     }
 
-    fn get_active_window(&self) -> Result<ActiveWindow, ()> {
+    fn get_active_window(&self) -> Result<ActiveWindow, Error> {
         const OPTIONS: CGWindowListOption =
             kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements;
         let window_list_info = unsafe { CGWindowListCopyWindowInfo(OPTIONS, kCGNullWindowID) };
@@ -112,12 +115,27 @@ impl PlatformApi for MacPlatformApi {
                     get_from_dict(dic_ref, "kCGWindowNumber")
                 {
                     let active_window = ActiveWindow {
-                        window_id: window_id.to_string(),
+                        window_id: WindowId::MacOS(window_id as u32),
                         process_id: active_window_pid as u64,
                         app_name,
+                        wm_instance: String::new(),
+                        wm_class: String::new(),
+                        gtk_application_id: None,
+                        window_role: None,
+                        // Window bounds include the title bar, the content area is not known
+                        client_rect: win_pos.clone(),
+                        frame_rect: win_pos.clone(),
                         position: win_pos,
                         title: win_title,
                         process_path,
+                        is_active: true,
+                        backend: Backend::MacOS,
+                        monitor: None,
+                        coordinate_space: CoordinateSpace::Logical,
+                        state: WindowState::empty(),
+                        window_type: WindowType::Normal,
+                        transient_for: None,
+                        workspace: None,
                     };
 
                     unsafe { CFRelease(window_list_info as CFTypeRef) }
@@ -129,7 +147,7 @@ impl PlatformApi for MacPlatformApi {
 
         unsafe { CFRelease(window_list_info as CFTypeRef) }
 
-        Err(())
+        Err(Error::NoActiveWindow)
     }
 }
 
@@ -0,0 +1,15 @@
+use windows::Win32::Foundation::E_ACCESSDENIED;
+
+use crate::common::error::Error;
+
+impl From<windows::core::Error> for Error {
+    fn from(err: windows::core::Error) -> Self {
+        if err.code() == E_ACCESSDENIED {
+            return Error::PermissionDenied;
+        }
+
+        // windows::core::Error holds a COM pointer and is not Send,
+        // so only its formatted message is kept
+        Error::Platform(err.to_string().into())
+    }
+}
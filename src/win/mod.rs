@@ -1,9 +1,12 @@
+mod error;
 mod platform_api;
 mod window_position;
 
+use std::sync::Arc;
+
 use crate::common::platform_api::PlatformApi;
 use platform_api::WindowsPlatformApi;
 
-pub fn init_platform_api() -> impl PlatformApi {
-    WindowsPlatformApi {}
+pub fn builtin_backends() -> Vec<Arc<dyn PlatformApi>> {
+    vec![Arc::new(WindowsPlatformApi {})]
 }
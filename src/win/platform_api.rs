@@ -4,6 +4,7 @@ use windows::core::{HSTRING, PCWSTR, PWSTR};
 use windows::w;
 use windows::Win32::Foundation::{CloseHandle, HANDLE, MAX_PATH};
 use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS};
+use windows::Win32::Graphics::Gdi::ClientToScreen;
 use windows::Win32::Storage::FileSystem::{
     GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW,
 };
@@ -12,13 +13,16 @@ use windows::Win32::System::Threading::{
 };
 use windows::Win32::UI::WindowsAndMessaging::{GetGUIThreadInfo, GUITHREADINFO};
 use windows::Win32::{
-    Foundation::{HWND, RECT},
+    Foundation::{HWND, POINT, RECT},
     UI::WindowsAndMessaging::{
-        GetForegroundWindow, GetWindowRect, GetWindowTextW, GetWindowThreadProcessId,
+        GetClientRect, GetForegroundWindow, GetWindowRect, GetWindowTextW, GetWindowThreadProcessId,
     },
 };
 
-use crate::{common::platform_api::PlatformApi, ActiveWindow, WindowPosition};
+use crate::{
+    common::platform_api::PlatformApi, ActiveWindow, Backend, CoordinateSpace, Error, WindowId,
+    WindowPosition, WindowState, WindowType,
+};
 
 use super::window_position::FromWinRect;
 
@@ -31,7 +35,11 @@ struct LangCodePage {
 pub struct WindowsPlatformApi {}
 
 impl PlatformApi for WindowsPlatformApi {
-    fn get_position(&self) -> Result<WindowPosition, ()> {
+    fn backend(&self) -> Backend {
+        Backend::Windows
+    }
+
+    fn get_position(&self) -> Result<WindowPosition, Error> {
         let active_window = get_foreground_window();
 
         if let Ok(win_position) = get_foreground_window_position(active_window) {
@@ -41,11 +49,17 @@ impl PlatformApi for WindowsPlatformApi {
         Ok(WindowPosition::new(0 as f64, 0 as f64, 0 as f64, 0 as f64))
     }
 
-    fn get_active_window(&self) -> Result<crate::ActiveWindow, ()> {
+    fn get_active_window(&self) -> Result<crate::ActiveWindow, Error> {
         let active_window_hwnd = get_foreground_window();
+        if active_window_hwnd.0 == 0 {
+            return Err(Error::NoActiveWindow);
+        }
 
         let win_position = get_foreground_window_position(active_window_hwnd)?;
         let active_window_position = WindowPosition::from_win_rect(&win_position);
+        let client_rect = get_window_client_rect(active_window_hwnd)
+            .map(|rect| WindowPosition::from_win_rect(&rect))
+            .unwrap_or_else(|_| active_window_position.clone());
         let active_window_title = get_window_title(active_window_hwnd)?;
         let mut process_id: u32 = 0;
         unsafe { GetWindowThreadProcessId(active_window_hwnd, Some(&mut process_id as *mut u32)) };
@@ -57,9 +71,23 @@ impl PlatformApi for WindowsPlatformApi {
             title: active_window_title,
             process_path: process_path.clone(),
             app_name,
+            wm_instance: String::new(),
+            wm_class: String::new(),
+            gtk_application_id: None,
+            window_role: None,
+            client_rect,
+            frame_rect: active_window_position.clone(),
             position: active_window_position,
             process_id: process_id as u64,
-            window_id: format!("{:?}", active_window_hwnd),
+            window_id: WindowId::Windows(active_window_hwnd.0),
+            is_active: true,
+            backend: Backend::Windows,
+            monitor: None,
+            coordinate_space: CoordinateSpace::Physical,
+            state: WindowState::empty(),
+            window_type: WindowType::Normal,
+            transient_for: None,
+            workspace: None,
         };
 
         //UWP app
@@ -108,7 +136,7 @@ fn get_foreground_window() -> HWND {
     unsafe { GetForegroundWindow() }
 }
 
-fn get_foreground_window_position(hwnd: HWND) -> Result<RECT, ()> {
+fn get_foreground_window_position(hwnd: HWND) -> Result<RECT, Error> {
     unsafe {
         let mut rect: RECT = std::mem::zeroed();
 
@@ -122,14 +150,39 @@ fn get_foreground_window_position(hwnd: HWND) -> Result<RECT, ()> {
 
         // Fall back to GetWindowRect if DwmGetWindowAttribute fails
         if result.is_err() && !GetWindowRect(hwnd, &mut rect).as_bool() {
-            return Err(());
+            return Err(windows::core::Error::from_win32().into());
         }
 
         Ok(rect)
     }
 }
 
-fn get_window_title(hwnd: HWND) -> Result<String, ()> {
+// Client area of the window in screen coordinates
+fn get_window_client_rect(hwnd: HWND) -> Result<RECT, Error> {
+    unsafe {
+        let mut rect: RECT = std::mem::zeroed();
+        if !GetClientRect(hwnd, &mut rect).as_bool() {
+            return Err(windows::core::Error::from_win32().into());
+        }
+
+        let mut origin = POINT {
+            x: rect.left,
+            y: rect.top,
+        };
+        if !ClientToScreen(hwnd, &mut origin).as_bool() {
+            return Err(windows::core::Error::from_win32().into());
+        }
+
+        Ok(RECT {
+            left: origin.x,
+            top: origin.y,
+            right: origin.x + rect.right - rect.left,
+            bottom: origin.y + rect.bottom - rect.top,
+        })
+    }
+}
+
+fn get_window_title(hwnd: HWND) -> Result<String, Error> {
     let title: String;
     unsafe {
         let mut v: Vec<u16> = vec![0; 255];
@@ -140,7 +193,7 @@ fn get_window_title(hwnd: HWND) -> Result<String, ()> {
     Ok(title)
 }
 
-fn get_process_path(process_id: u32) -> Result<PathBuf, ()> {
+fn get_process_path(process_id: u32) -> Result<PathBuf, Error> {
     let process_handle = get_process_handle(process_id)?;
     let mut lpdw_size: u32 = MAX_PATH;
     let mut process_path_raw = vec![0; MAX_PATH as usize];
@@ -154,22 +207,27 @@ fn get_process_path(process_id: u32) -> Result<PathBuf, ()> {
             &mut lpdw_size,
         );
 
-        close_process_handle(process_handle);
-
         if !success.as_bool() {
-            return Err(());
+            let err = windows::core::Error::from_win32();
+            close_process_handle(process_handle);
+            return Err(err.into());
         }
 
-        process_path_pwstr.to_string().map_err(|_| ())?
+        close_process_handle(process_handle);
+
+        process_path_pwstr
+            .to_string()
+            .map_err(|err| Error::Platform(Box::new(err)))?
     };
 
     Ok(Path::new(&process_path).to_path_buf())
 }
 
-fn get_process_name(process_path: &Path) -> Result<String, ()> {
-    let file_description = get_file_description(process_path);
-    if file_description.is_ok() && !file_description.as_ref().unwrap().is_empty() {
-        return file_description;
+fn get_process_name(process_path: &Path) -> Result<String, Error> {
+    if let Ok(file_description) = get_file_description(process_path) {
+        if !file_description.is_empty() {
+            return Ok(file_description);
+        }
     }
 
     let process_file_name = process_path
@@ -258,10 +316,10 @@ fn get_file_description(process_path: &Path) -> Result<String, ()> {
     Ok(file_description)
 }
 
-fn get_process_handle(process_id: u32) -> Result<HANDLE, ()> {
+fn get_process_handle(process_id: u32) -> Result<HANDLE, Error> {
     let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id) };
 
-    handle.map_err(|_| ())
+    handle.map_err(Error::from)
 }
 
 fn close_process_handle(process_handle: HANDLE) {
@@ -0,0 +1,51 @@
+use active_win_pos_rs::{ActiveWindowSession, Backend, Error};
+
+#[test]
+fn parses_backend_names() {
+    let backends = [
+        Backend::Auto,
+        Backend::X11,
+        Backend::KWin,
+        Backend::Windows,
+        Backend::MacOS,
+    ];
+
+    for backend in backends {
+        assert_eq!(backend.to_string().parse::<Backend>().unwrap(), backend);
+    }
+    assert_eq!(" KWin ".parse::<Backend>().unwrap(), Backend::KWin);
+    assert!(matches!(
+        "gnome".parse::<Backend>(),
+        Err(Error::InvalidBackend(name)) if name == "gnome"
+    ));
+}
+
+#[test]
+fn builder_rejects_unavailable_backend() {
+    let unavailable = if cfg!(target_os = "windows") {
+        Backend::MacOS
+    } else {
+        Backend::Windows
+    };
+
+    assert!(!unavailable.is_available());
+    assert!(matches!(
+        ActiveWindowSession::builder().backend(unavailable).build(),
+        Err(Error::BackendUnavailable(_))
+    ));
+    assert!(ActiveWindowSession::builder()
+        .backend(Backend::Auto)
+        .build()
+        .is_ok());
+}
+
+// The only test in this binary that touches the environment
+#[test]
+fn env_var_with_unknown_backend_is_an_error() {
+    std::env::set_var("ACTIVE_WIN_BACKEND", "gnome");
+
+    let result = ActiveWindowSession::new().get_active_window();
+
+    std::env::remove_var("ACTIVE_WIN_BACKEND");
+    assert!(matches!(result, Err(Error::InvalidBackend(name)) if name == "gnome"));
+}
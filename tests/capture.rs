@@ -0,0 +1,60 @@
+#![cfg(target_os = "linux")]
+
+use std::env;
+
+use active_win_pos_rs::{CaptureArea, WindowId, WindowPosition};
+use xcb::{x, Xid};
+
+// Needs an X server, run it under Xvfb with `xvfb-run cargo test --test capture`
+#[test]
+fn captures_a_window_from_the_screen() {
+    if env::var_os("DISPLAY").is_none() || env::var_os("WAYLAND_DISPLAY").is_some() {
+        eprintln!("skipped, no X11 display");
+        return;
+    }
+
+    let (conn, screen_num) = xcb::Connection::connect(None).unwrap();
+    let screen = conn.get_setup().roots().nth(screen_num as usize).unwrap();
+
+    // Not managed by a WM, so it is neither moved nor decorated and stays on top
+    let window: x::Window = conn.generate_id();
+    conn.send_and_check_request(&x::CreateWindow {
+        depth: x::COPY_FROM_PARENT as u8,
+        wid: window,
+        parent: screen.root(),
+        x: 10,
+        y: 20,
+        width: 64,
+        height: 48,
+        border_width: 0,
+        class: x::WindowClass::InputOutput,
+        visual: screen.root_visual(),
+        value_list: &[
+            x::Cw::BackPixel(screen.white_pixel()),
+            x::Cw::OverrideRedirect(true),
+            x::Cw::EventMask(x::EventMask::EXPOSURE),
+        ],
+    })
+    .unwrap();
+    conn.send_and_check_request(&x::MapWindow { window })
+        .unwrap();
+    // The background is drawn by the time the window is exposed
+    while !matches!(
+        conn.wait_for_event().unwrap(),
+        xcb::Event::X(x::Event::Expose(_))
+    ) {}
+
+    let window_id = WindowId::X11(window.resource_id());
+    let screenshot = active_win_pos_rs::capture_window(&window_id, CaptureArea::Client).unwrap();
+
+    assert_eq!(
+        screenshot.position,
+        WindowPosition::new(10.0, 20.0, 64.0, 48.0)
+    );
+    assert_eq!((screenshot.width, screenshot.height), (64, 48));
+    assert_eq!(screenshot.rgba.len(), 64 * 48 * 4);
+    assert!(screenshot.rgba.iter().all(|byte| *byte == u8::MAX));
+
+    conn.send_and_check_request(&x::DestroyWindow { window })
+        .unwrap();
+}
@@ -0,0 +1,63 @@
+use active_win_pos_rs::{ActiveWindow, CoordinateSpace, Monitor, WindowPosition};
+
+fn hidpi_monitor(coordinate_space: CoordinateSpace) -> Monitor {
+    Monitor {
+        name: "eDP-1".to_owned(),
+        position: WindowPosition::new(0.0, 0.0, 1920.0, 1200.0),
+        width_mm: 301,
+        height_mm: 188,
+        scale_factor: 2.0,
+        coordinate_space,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn converts_between_logical_and_physical() {
+    let logical = WindowPosition::new(10.0, 20.0, 300.0, 150.0);
+    let physical = logical.to_physical(1.5);
+
+    assert_eq!(physical, WindowPosition::new(15.0, 30.0, 450.0, 225.0));
+    assert_eq!(physical.to_logical(1.5), logical);
+}
+
+#[test]
+fn window_position_uses_the_monitor_scale() {
+    let window = ActiveWindow {
+        position: WindowPosition::new(100.0, 50.0, 800.0, 600.0),
+        monitor: Some(hidpi_monitor(CoordinateSpace::Logical)),
+        coordinate_space: CoordinateSpace::Logical,
+        ..Default::default()
+    };
+
+    assert_eq!(window.scale_factor(), 2.0);
+    assert_eq!(window.logical_position(), window.position);
+    assert_eq!(
+        window.physical_position(),
+        WindowPosition::new(200.0, 100.0, 1600.0, 1200.0)
+    );
+
+    let unknown_monitor = ActiveWindow {
+        monitor: None,
+        ..window
+    };
+    assert_eq!(
+        unknown_monitor.physical_position(),
+        unknown_monitor.position
+    );
+}
+
+#[test]
+fn monitor_dpi_uses_physical_pixels() {
+    let logical = hidpi_monitor(CoordinateSpace::Logical);
+    let physical = Monitor {
+        position: logical.physical_position(),
+        ..hidpi_monitor(CoordinateSpace::Physical)
+    };
+
+    let dpi = logical.dpi().unwrap();
+    assert!((dpi - 324.0).abs() < 0.1, "{}", dpi);
+    assert_eq!(physical.dpi(), logical.dpi());
+    assert_eq!(Monitor::default().dpi(), None);
+    assert_eq!(Monitor::default().scale_factor, 1.0);
+}
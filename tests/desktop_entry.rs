@@ -0,0 +1,94 @@
+#![cfg(target_os = "linux")]
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use active_win_pos_rs::ActiveWindow;
+
+fn write_desktop_file(applications: &Path, name: &str, contents: &str) {
+    let path = applications.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+// The lookup reads the environment, so everything runs in one test
+#[test]
+fn resolves_desktop_entries() {
+    let data_home = env::temp_dir().join(format!("active-win-pos-rs-xdg-{}", std::process::id()));
+    let applications = data_home.join("applications");
+    write_desktop_file(
+        &applications,
+        "org.mozilla.firefox.desktop",
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Firefox\n\
+         Name[de]=Firefox Browser\n\
+         GenericName=Web Browser\n\
+         GenericName[de]=Webbrowser\n\
+         Icon=org.mozilla.firefox\n\
+         Exec=/app/bin/firefox %u\n\
+         Categories=Network;WebBrowser;\n\
+         \n\
+         [Desktop Action new-window]\n\
+         Name=New Window\n",
+    );
+    write_desktop_file(
+        &applications,
+        "vendor/editor.desktop",
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Text\\sEditor\n\
+         Exec=env GDK_BACKEND=x11 editor-bin --new-window\n\
+         StartupWMClass=Edit-Main\n",
+    );
+    write_desktop_file(
+        &applications,
+        "hidden.desktop",
+        "[Desktop Entry]\nType=Application\nName=Hidden\nExec=hidden\nHidden=true\n",
+    );
+    // Symlinked directories are not followed, this one would never end
+    std::os::unix::fs::symlink(&applications, applications.join("vendor/loop")).unwrap();
+
+    env::set_var("XDG_DATA_HOME", &data_home);
+    env::set_var("XDG_DATA_DIRS", data_home.join("missing"));
+    env::set_var("LC_ALL", "de_AT.UTF-8");
+
+    let firefox = ActiveWindow {
+        wm_class: "firefox".to_owned(),
+        gtk_application_id: Some("org.mozilla.firefox".to_owned()),
+        ..Default::default()
+    };
+    let entry = firefox.desktop_entry().unwrap().unwrap();
+    assert_eq!(entry.id, "org.mozilla.firefox.desktop");
+    assert_eq!(entry.path, applications.join("org.mozilla.firefox.desktop"));
+    assert_eq!(entry.name, "Firefox Browser");
+    assert_eq!(entry.generic_name.as_deref(), Some("Webbrowser"));
+    assert_eq!(entry.icon.as_deref(), Some("org.mozilla.firefox"));
+    assert_eq!(entry.categories, ["Network", "WebBrowser"]);
+
+    let by_class = ActiveWindow {
+        wm_class: "edit-main".to_owned(),
+        ..Default::default()
+    };
+    let entry = by_class.desktop_entry().unwrap().unwrap();
+    assert_eq!(entry.id, "vendor-editor.desktop");
+    assert_eq!(entry.name, "Text Editor");
+
+    let by_executable = ActiveWindow {
+        process_path: PathBuf::from("/usr/lib/editor/editor-bin"),
+        ..Default::default()
+    };
+    assert_eq!(
+        by_executable.desktop_entry().unwrap().unwrap().id,
+        "vendor-editor.desktop"
+    );
+
+    let hidden = ActiveWindow {
+        process_path: PathBuf::from("/usr/bin/hidden"),
+        ..Default::default()
+    };
+    assert_eq!(hidden.desktop_entry().unwrap(), None);
+
+    fs::remove_dir_all(&data_home).unwrap();
+}
@@ -0,0 +1,176 @@
+#![cfg(feature = "mock")]
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use active_win_pos_rs::{
+    ActiveWindow, ActiveWindowSession, Backend, CaptureArea, Error, MockBackend, Monitor,
+    WindowIcon, WindowId, WindowPosition, WindowState, Workspace,
+};
+
+fn window(id: u32, title: &str) -> ActiveWindow {
+    ActiveWindow {
+        title: title.to_owned(),
+        window_id: WindowId::X11(id),
+        ..Default::default()
+    }
+}
+
+// The mock is installed globally, so everything runs in one test
+#[test]
+fn mock_backend_serves_the_script() {
+    let mock = MockBackend::new();
+    mock.install();
+
+    assert!(matches!(
+        active_win_pos_rs::get_active_window(),
+        Err(Error::NoActiveWindow)
+    ));
+
+    mock.set_active_window(window(1, "Editor"));
+    mock.set_windows(vec![window(1, "Editor"), window(2, "Browser")]);
+
+    let session = ActiveWindowSession::new();
+    let active_window = session.get_active_window().unwrap();
+    assert_eq!(active_window.title, "Editor");
+    assert_eq!(active_window.backend, Backend::Custom("mock"));
+
+    let windows = session.list_windows().unwrap();
+    let active = windows
+        .iter()
+        .map(|window| window.is_active)
+        .collect::<Vec<_>>();
+    assert_eq!(active, [true, false]);
+
+    let left = Monitor {
+        name: "DP-1".to_owned(),
+        position: WindowPosition::new(0.0, 0.0, 1920.0, 1080.0),
+        is_primary: true,
+        ..Default::default()
+    };
+    let right = Monitor {
+        name: "DP-2".to_owned(),
+        position: WindowPosition::new(1920.0, 0.0, 1920.0, 1080.0),
+        ..Default::default()
+    };
+    mock.set_monitors(vec![left.clone(), right.clone()]);
+    assert_eq!(
+        active_win_pos_rs::list_monitors().unwrap(),
+        [left, right.clone()]
+    );
+
+    // Mostly on the right monitor
+    mock.set_active_window(ActiveWindow {
+        position: WindowPosition::new(1800.0, 100.0, 800.0, 600.0),
+        ..window(1, "Editor")
+    });
+    let active_window = session.get_active_window().unwrap();
+    assert_eq!(active_window.monitor, Some(right));
+    assert_eq!(
+        active_window.position_on_monitor(),
+        Some(WindowPosition::new(-120.0, 100.0, 800.0, 600.0))
+    );
+
+    let workspaces = vec![
+        Workspace {
+            index: 0,
+            name: "Main".to_owned(),
+            is_current: true,
+        },
+        Workspace {
+            index: 1,
+            name: "Chat".to_owned(),
+            is_current: false,
+        },
+    ];
+    mock.set_workspaces(workspaces.clone());
+    assert_eq!(session.workspaces().unwrap(), workspaces);
+
+    assert_eq!(session.idle_time().unwrap(), Duration::ZERO);
+    mock.set_idle_time(Duration::from_secs(300));
+    assert_eq!(
+        active_win_pos_rs::idle_time().unwrap(),
+        Duration::from_secs(300)
+    );
+
+    let icon = WindowIcon {
+        width: 1,
+        height: 1,
+        rgba: vec![0xff; 4],
+    };
+    mock.set_window_icons(WindowId::X11(1), vec![icon.clone()]);
+    assert_eq!(session.get_window_icons(&WindowId::X11(1)).unwrap(), [icon]);
+    assert!(session
+        .get_window_icons(&WindowId::X11(2))
+        .unwrap()
+        .is_empty());
+
+    let id = WindowId::X11(1);
+    assert!(matches!(
+        session.capture_window(&id, CaptureArea::Client),
+        Err(Error::Unsupported)
+    ));
+    assert!(matches!(
+        session.activate_window(&id),
+        Err(Error::Unsupported)
+    ));
+    assert!(matches!(
+        session.set_window_state(&id, WindowState::FULLSCREEN, true),
+        Err(Error::Unsupported)
+    ));
+    assert!(matches!(session.close_window(&id), Err(Error::Unsupported)));
+
+    // Other backends are not reached, also when forced
+    let x11 = ActiveWindowSession::builder()
+        .backend(Backend::X11)
+        .build()
+        .unwrap();
+    assert_eq!(x11.get_active_window().unwrap().title, "Editor");
+
+    mock.set_error(|| Error::PermissionDenied);
+    assert!(matches!(
+        session.get_active_window(),
+        Err(Error::PermissionDenied)
+    ));
+
+    // Changes are timed from when the mock was created
+    let mock = MockBackend::new();
+    mock.set_active_window(window(1, "Editor"));
+    mock.push_change(Duration::from_millis(100), window(2, "Browser"));
+    mock.push_error(Duration::from_millis(200), || Error::NoActiveWindow);
+    mock.push_change(Duration::from_millis(300), window(1, "Editor"));
+    mock.install();
+
+    let start = Instant::now();
+    let changes = session.watch_active_window().unwrap().collect::<Vec<_>>();
+    assert!(start.elapsed() >= Duration::from_millis(250));
+    assert!(matches!(
+        &changes[..],
+        [Ok(editor), Ok(browser), Err(Error::NoActiveWindow), Ok(editor_again)]
+            if editor.title == "Editor" && browser.title == "Browser" && editor_again.title == "Editor"
+    ));
+    assert_eq!(session.get_active_window().unwrap().title, "Editor");
+
+    // Stopping ends a watcher that waits for the next change
+    let mock = MockBackend::new();
+    mock.set_active_window(window(1, "Editor"));
+    mock.push_change(Duration::from_secs(60), window(2, "Browser"));
+    mock.install();
+
+    let mut watcher = session.watch_active_window().unwrap();
+    assert_eq!(watcher.next().unwrap().unwrap().title, "Editor");
+
+    let stop = watcher.stop_handle();
+    let stopper = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        stop.stop();
+    });
+    assert!(watcher.next().is_none());
+    stopper.join().unwrap();
+
+    MockBackend::uninstall();
+    assert!(!matches!(
+        session.get_active_window(),
+        Ok(window) if window.backend == Backend::Custom("mock")
+    ));
+}
@@ -0,0 +1,35 @@
+#![cfg(target_os = "linux")]
+
+use std::env;
+use std::process;
+use std::time::SystemTime;
+
+use active_win_pos_rs::{ActiveWindow, Error, ProcessInfo};
+
+#[test]
+fn reads_own_process() {
+    let window = ActiveWindow {
+        process_id: process::id().into(),
+        ..Default::default()
+    };
+    let info = window.process_info().unwrap();
+
+    assert_eq!(info.pid, u64::from(process::id()));
+    assert_eq!(info.cmdline, env::args().collect::<Vec<_>>());
+    assert_eq!(info.cwd, Some(env::current_dir().unwrap()));
+    assert!(info.parent_pid.is_some_and(|ppid| ppid > 0));
+    assert!(info.uid.is_some());
+    assert!(info
+        .start_time
+        .is_some_and(|start| start <= SystemTime::now()));
+    assert!(!info.exe_deleted);
+}
+
+#[test]
+fn missing_process_is_an_error() {
+    // Above the kernel's highest possible pid
+    assert!(matches!(
+        ProcessInfo::for_pid(u64::from(u32::MAX)),
+        Err(Error::Platform(_))
+    ));
+}
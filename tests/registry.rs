@@ -0,0 +1,170 @@
+use std::sync::{Arc, Mutex};
+
+use active_win_pos_rs::{
+    register_backend, ActiveWindow, ActiveWindowSession, Backend, Error, PlatformApi, WindowId,
+    WindowPosition, WindowState,
+};
+
+struct FixedBackend;
+
+impl PlatformApi for FixedBackend {
+    fn backend(&self) -> Backend {
+        Backend::Custom("fixed")
+    }
+
+    fn get_active_window(&self) -> Result<ActiveWindow, Error> {
+        Ok(ActiveWindow {
+            title: "Fixed".to_owned(),
+            window_id: WindowId::X11(42),
+            position: WindowPosition::new(1.0, 2.0, 3.0, 4.0),
+            is_active: true,
+            backend: self.backend(),
+            ..Default::default()
+        })
+    }
+}
+
+// Not available, so `Backend::Auto` passes it over while it can still be forced
+struct UnavailableBackend;
+
+impl PlatformApi for UnavailableBackend {
+    fn backend(&self) -> Backend {
+        Backend::Custom("unavailable")
+    }
+
+    fn is_available(&self) -> bool {
+        false
+    }
+
+    fn get_active_window(&self) -> Result<ActiveWindow, Error> {
+        Err(Error::NoActiveWindow)
+    }
+}
+
+// Registered backends are global to this test binary, so tests running in parallel see each
+// other's backends. Only this test registers available ones, `RecordingBackend` is never picked
+// by `Backend::Auto`.
+#[test]
+fn registered_backends_take_part_in_queries() {
+    register_backend(UnavailableBackend);
+    register_backend(FixedBackend);
+
+    let window = ActiveWindowSession::new().get_active_window().unwrap();
+    assert_eq!(window.title, "Fixed");
+    assert_eq!(window.backend, Backend::Custom("fixed"));
+    assert_eq!(
+        active_win_pos_rs::get_position().unwrap(),
+        WindowPosition::new(1.0, 2.0, 3.0, 4.0)
+    );
+
+    let session = ActiveWindowSession::builder()
+        .backend(Backend::Custom("unavailable"))
+        .build()
+        .unwrap();
+    assert!(matches!(
+        session.get_active_window(),
+        Err(Error::NoActiveWindow)
+    ));
+    assert!(matches!(session.list_windows(), Err(Error::Unsupported)));
+
+    assert_eq!(
+        "Fixed".parse::<Backend>().unwrap(),
+        Backend::Custom("fixed")
+    );
+    assert!(Backend::Custom("fixed").is_available());
+    assert!(!Backend::Custom("missing").is_available());
+}
+
+// Records the window actions it is asked for, only ever used when forced
+#[derive(Clone, Default)]
+struct RecordingBackend {
+    actions: Arc<Mutex<Vec<String>>>,
+}
+
+impl RecordingBackend {
+    fn record(&self, window_id: &WindowId, action: String) -> Result<(), Error> {
+        match window_id {
+            WindowId::X11(1) => {
+                self.actions.lock().unwrap().push(action);
+                Ok(())
+            }
+            window_id => Err(Error::WindowNotFound(window_id.clone())),
+        }
+    }
+}
+
+impl PlatformApi for RecordingBackend {
+    fn backend(&self) -> Backend {
+        Backend::Custom("recording")
+    }
+
+    fn is_available(&self) -> bool {
+        false
+    }
+
+    fn get_active_window(&self) -> Result<ActiveWindow, Error> {
+        Err(Error::NoActiveWindow)
+    }
+
+    fn activate_window(&self, window_id: &WindowId) -> Result<(), Error> {
+        self.record(window_id, "activate".to_owned())
+    }
+
+    fn move_resize_window(
+        &self,
+        window_id: &WindowId,
+        position: &WindowPosition,
+    ) -> Result<(), Error> {
+        self.record(window_id, format!("move_resize {:?}", position))
+    }
+
+    fn set_window_state(
+        &self,
+        window_id: &WindowId,
+        state: WindowState,
+        enable: bool,
+    ) -> Result<(), Error> {
+        self.record(window_id, format!("set_state {:?} {}", state, enable))
+    }
+
+    fn close_window(&self, window_id: &WindowId) -> Result<(), Error> {
+        self.record(window_id, "close".to_owned())
+    }
+}
+
+#[test]
+fn window_actions_reach_the_backend() {
+    let backend = RecordingBackend::default();
+    register_backend(backend.clone());
+
+    let session = ActiveWindowSession::builder()
+        .backend(Backend::Custom("recording"))
+        .build()
+        .unwrap();
+    let window_id = WindowId::X11(1);
+    session.activate_window(&window_id).unwrap();
+    session
+        .move_resize_window(&window_id, &WindowPosition::new(10.0, 20.0, 640.0, 480.0))
+        .unwrap();
+    session
+        .set_window_state(&window_id, WindowState::MAXIMIZED, true)
+        .unwrap();
+    session.close_window(&window_id).unwrap();
+
+    assert!(matches!(
+        session.close_window(&WindowId::X11(2)),
+        Err(Error::WindowNotFound(WindowId::X11(2)))
+    ));
+    assert_eq!(
+        *backend.actions.lock().unwrap(),
+        [
+            "activate".to_owned(),
+            format!(
+                "move_resize {:?}",
+                WindowPosition::new(10.0, 20.0, 640.0, 480.0)
+            ),
+            format!("set_state {:?} true", WindowState::MAXIMIZED),
+            "close".to_owned(),
+        ]
+    );
+}
@@ -0,0 +1,161 @@
+#![cfg(feature = "serde")]
+
+use std::path::PathBuf;
+
+use active_win_pos_rs::{
+    ActiveWindow, Backend, CoordinateSpace, Monitor, WindowId, WindowPosition, WindowState,
+    WindowType, Workspace,
+};
+
+fn sample_window() -> ActiveWindow {
+    ActiveWindow {
+        title: "README.md - Visual Studio Code".to_owned(),
+        process_path: PathBuf::from("/usr/share/code/code"),
+        app_name: "Code".to_owned(),
+        wm_instance: "code".to_owned(),
+        wm_class: "Code".to_owned(),
+        gtk_application_id: Some("com.visualstudio.code".to_owned()),
+        window_role: Some("browser-window".to_owned()),
+        window_id: WindowId::KWin("{6e0d6a3d-95a6-4c89-9b1b-2f3a0f4f2b6e}".to_owned()),
+        process_id: 4242,
+        position: WindowPosition::new(0.0, 27.0, 1920.0, 1053.0),
+        client_rect: WindowPosition::new(0.0, 57.0, 1920.0, 1023.0),
+        frame_rect: WindowPosition::new(0.0, 27.0, 1920.0, 1053.0),
+        is_active: true,
+        backend: Backend::KWin,
+        monitor: Some(Monitor {
+            name: "DP-1".to_owned(),
+            position: WindowPosition::new(0.0, 0.0, 1920.0, 1080.0),
+            is_primary: true,
+            refresh_rate: 60.0,
+            width_mm: 527,
+            height_mm: 296,
+            scale_factor: 1.25,
+            coordinate_space: CoordinateSpace::Logical,
+        }),
+        coordinate_space: CoordinateSpace::Logical,
+        state: WindowState::MAXIMIZED | WindowState::ABOVE,
+        window_type: WindowType::Dialog,
+        transient_for: Some(WindowId::KWin(
+            "{0b3f5a8e-1c2d-4e6f-8a9b-7c6d5e4f3a2b}".to_owned(),
+        )),
+        workspace: Some(Workspace {
+            index: 1,
+            name: "Work".to_owned(),
+            is_current: true,
+        }),
+    }
+}
+
+#[test]
+fn active_window_round_trip() {
+    let window = sample_window();
+
+    let json = serde_json::to_string(&window).unwrap();
+    let restored: ActiveWindow = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored, window);
+    assert_eq!(restored.title, window.title);
+    assert_eq!(restored.process_path, window.process_path);
+    assert_eq!(restored.app_name, window.app_name);
+    assert_eq!(restored.wm_instance, window.wm_instance);
+    assert_eq!(restored.wm_class, window.wm_class);
+    assert_eq!(restored.gtk_application_id, window.gtk_application_id);
+    assert_eq!(restored.window_role, window.window_role);
+    assert_eq!(restored.position, window.position);
+    assert_eq!(restored.client_rect, window.client_rect);
+    assert_eq!(restored.frame_rect, window.frame_rect);
+    assert_eq!(restored.is_active, window.is_active);
+    assert_eq!(restored.backend, window.backend);
+    assert_eq!(restored.monitor, window.monitor);
+    assert_eq!(restored.coordinate_space, window.coordinate_space);
+    assert_eq!(restored.state, window.state);
+    assert_eq!(restored.window_type, window.window_type);
+    assert_eq!(restored.transient_for, window.transient_for);
+    assert_eq!(restored.workspace, window.workspace);
+}
+
+#[test]
+fn active_window_layout() {
+    let json = serde_json::to_value(sample_window()).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "title": "README.md - Visual Studio Code",
+            "process_path": "/usr/share/code/code",
+            "app_name": "Code",
+            "wm_instance": "code",
+            "wm_class": "Code",
+            "gtk_application_id": "com.visualstudio.code",
+            "window_role": "browser-window",
+            "window_id": "{6e0d6a3d-95a6-4c89-9b1b-2f3a0f4f2b6e}",
+            "process_id": 4242,
+            "position": { "x": 0.0, "y": 27.0, "width": 1920.0, "height": 1053.0 },
+            "client_rect": { "x": 0.0, "y": 57.0, "width": 1920.0, "height": 1023.0 },
+            "frame_rect": { "x": 0.0, "y": 27.0, "width": 1920.0, "height": 1053.0 },
+            "is_active": true,
+            "backend": "kwin",
+            "monitor": {
+                "name": "DP-1",
+                "position": { "x": 0.0, "y": 0.0, "width": 1920.0, "height": 1080.0 },
+                "is_primary": true,
+                "refresh_rate": 60.0,
+                "width_mm": 527,
+                "height_mm": 296,
+                "scale_factor": 1.25,
+                "coordinate_space": "logical",
+            },
+            "coordinate_space": "logical",
+            "state": "MAXIMIZED_VERT | MAXIMIZED_HORZ | ABOVE",
+            "window_type": "dialog",
+            "transient_for": "{0b3f5a8e-1c2d-4e6f-8a9b-7c6d5e4f3a2b}",
+            "workspace": { "index": 1, "name": "Work", "is_current": true },
+        })
+    );
+}
+
+#[test]
+fn active_window_missing_fields_use_defaults() {
+    let restored: ActiveWindow =
+        serde_json::from_str(r#"{ "title": "Terminal", "process_id": 7 }"#).unwrap();
+
+    assert_eq!(restored.title, "Terminal");
+    assert_eq!(restored.process_id, 7);
+    assert_eq!(restored.wm_instance, "");
+    assert_eq!(restored.wm_class, "");
+    assert_eq!(restored.gtk_application_id, None);
+    assert_eq!(restored.window_role, None);
+    assert_eq!(restored.position, WindowPosition::default());
+    assert_eq!(restored.client_rect, WindowPosition::default());
+    assert_eq!(restored.frame_rect, WindowPosition::default());
+    assert!(!restored.is_active);
+    assert_eq!(restored.backend, Backend::Auto);
+    assert_eq!(restored.monitor, None);
+    assert_eq!(restored.coordinate_space, CoordinateSpace::Physical);
+    assert!(restored.state.is_empty());
+    assert_eq!(restored.window_type, WindowType::Normal);
+    assert_eq!(restored.transient_for, None);
+    assert_eq!(restored.workspace, None);
+}
+
+#[test]
+fn window_id_is_serialized_as_string() {
+    let json = serde_json::to_string(&WindowId::Windows(9700584)).unwrap();
+    assert_eq!(json, r#""HWND(9700584)""#);
+
+    let restored: WindowId = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, WindowId::Windows(9700584));
+
+    assert!(serde_json::from_str::<WindowId>(r#""not a window""#).is_err());
+}
+
+#[test]
+fn window_position_round_trip() {
+    let position = WindowPosition::new(-8.0, 12.5, 800.0, 600.0);
+
+    let json = serde_json::to_string(&position).unwrap();
+    let restored: WindowPosition = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored, position);
+}
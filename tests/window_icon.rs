@@ -0,0 +1,29 @@
+use active_win_pos_rs::WindowIcon;
+
+fn icon(size: u32) -> WindowIcon {
+    WindowIcon {
+        width: size,
+        height: size,
+        rgba: vec![0xff; (size * size * 4) as usize],
+    }
+}
+
+#[test]
+fn picks_the_closest_size() {
+    let icons = [icon(16), icon(32), icon(48), icon(128)];
+
+    assert_eq!(WindowIcon::closest(&icons, 16).unwrap().width, 16);
+    assert_eq!(WindowIcon::closest(&icons, 30).unwrap().width, 32);
+    // Equally far from 32 and 48
+    assert_eq!(WindowIcon::closest(&icons, 40).unwrap().width, 48);
+    assert_eq!(WindowIcon::closest(&icons, 512).unwrap().width, 128);
+    assert_eq!(WindowIcon::closest(&[], 32), None);
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn encodes_png() {
+    let png = icon(16).to_png().unwrap();
+
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+}
@@ -0,0 +1,49 @@
+use std::collections::HashSet;
+
+use active_win_pos_rs::WindowId;
+
+#[test]
+fn display_matches_platform_format() {
+    assert_eq!(WindowId::X11(81788931).to_string(), "81788931");
+    assert_eq!(WindowId::Windows(9700584).to_string(), "HWND(9700584)");
+    assert_eq!(WindowId::MacOS(1234).to_string(), "1234");
+    assert_eq!(
+        WindowId::KWin("{6e0d6a3d-95a6-4c89-9b1b-2f3a0f4f2b6e}".to_owned()).to_string(),
+        "{6e0d6a3d-95a6-4c89-9b1b-2f3a0f4f2b6e}"
+    );
+}
+
+#[test]
+fn parses_back_from_display() {
+    let native = if cfg!(target_os = "macos") {
+        WindowId::MacOS(1234)
+    } else {
+        WindowId::X11(1234)
+    };
+    let ids = [
+        native,
+        WindowId::Windows(9700584),
+        WindowId::KWin("{6e0d6a3d-95a6-4c89-9b1b-2f3a0f4f2b6e}".to_owned()),
+    ];
+
+    for id in ids {
+        assert_eq!(id.to_string().parse::<WindowId>().unwrap(), id);
+    }
+}
+
+#[test]
+fn rejects_invalid_ids() {
+    for id in ["", "{}", "HWND()", "HWND(abc)", "window", "-1x"] {
+        assert!(id.parse::<WindowId>().is_err(), "{:?} parsed", id);
+    }
+}
+
+#[test]
+fn can_be_hashed() {
+    let ids: HashSet<WindowId> = ["1", "2", "1", "HWND(1)"]
+        .iter()
+        .map(|id| id.parse().unwrap())
+        .collect();
+
+    assert_eq!(ids.len(), 3);
+}